@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// the solution is compiled as a module, so its `main` and tests are unused here
+#[path = "../../src/bin/04.rs"]
+#[allow(dead_code, unexpected_cfgs)]
+mod solution;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = solution::part_one(input);
+        let _ = solution::part_two(input);
+    }
+});
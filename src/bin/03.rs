@@ -11,7 +11,7 @@ struct Number {
 }
 
 fn build_number(value: u32, positions: &[Position]) -> Number {
-    let neighbors = positions.iter().flat_map(|it| it.neighbors(Position::moves(true, false))).collect();
+    let neighbors = positions.iter().flat_map(|it| it.neighbors8()).collect();
     Number { value, neighbors }
 }
 
@@ -1,4 +1,5 @@
-use itertools::Itertools;
+use advent_of_code::template::SolutionError;
+use advent_of_code::util::parse::{key_value, parse_field, parse_lines, scan, try_integers, FromLine};
 use std::collections::HashSet;
 
 advent_of_code::solution!(4);
@@ -10,24 +11,20 @@ struct Card {
     own_numbers: Vec<u32>,
 }
 
-impl Card {
-    fn from_line(line: &str) -> Card {
-        let (card_text, numbers) = line.split_once(": ").unwrap();
-        let id_text = card_text.split_whitespace().get(1..=1).next().unwrap();
-        let id = id_text.parse().unwrap();
-        let (winning_text, own_text) = numbers.split_once(" | ").unwrap();
-        let winning_numbers = winning_text
-            .split_whitespace()
-            .filter_map(|it| it.parse().ok())
-            .collect();
-        let own_numbers = own_text
-            .split_whitespace()
-            .filter_map(|it| it.parse().ok())
-            .collect();
+impl FromLine for Card {
+    fn from_line(line: &str) -> Result<Card, SolutionError> {
+        let (card_text, numbers) = key_value(line)?;
+        let [id_text] = scan(card_text, "Card {}")?;
+        let id = parse_field(id_text)?;
+        let [winning_text, own_text] = scan(numbers, "{} | {}")?;
+        let winning_numbers = try_integers(winning_text).collect::<Result<_, _>>()?;
+        let own_numbers = try_integers(own_text).collect::<Result<_, _>>()?;
 
-        Card { id, winning_numbers, own_numbers }
+        Ok(Card { id, winning_numbers, own_numbers })
     }
+}
 
+impl Card {
     fn points(self) -> u32 {
         self.own_numbers.iter()
             .filter(|it| self.winning_numbers.contains(it))
@@ -41,22 +38,18 @@ impl Card {
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let result = input
-        .lines()
-        .map(Card::from_line)
+pub fn part_one(input: &str) -> Result<u32, SolutionError> {
+    let result = parse_lines::<Card>(input)?
+        .into_iter()
         .map(|it| it.points())
         .filter(|it| *it > 0)
         .map(|it| 2u32.pow(it - 1))
         .sum();
-    Some(result)
+    Ok(result)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let cards: Vec<Card> = input
-        .lines()
-        .map(Card::from_line)
-        .collect();
+pub fn part_two(input: &str) -> Result<u32, SolutionError> {
+    let cards: Vec<Card> = parse_lines(input)?;
 
     let mut copies: Vec<u32> = vec![1; cards.len()];
 
@@ -66,7 +59,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
     }
 
-    Some(copies.iter().sum())
+    Ok(copies.iter().sum())
 }
 
 #[cfg(test)]
@@ -76,12 +69,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(13));
+        assert_eq!(result.unwrap(), 13);
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(30));
+        assert_eq!(result.unwrap(), 30);
     }
 }
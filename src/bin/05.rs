@@ -1,105 +1,57 @@
-use itertools::Itertools;
-use regex::Regex;
-use std::cmp;
+use advent_of_code::template::{Answer, SolutionError};
+use advent_of_code::util::parse::{blocks, key_value, parse_field, scan, try_integers};
+use advent_of_code::util::ranges::{PiecewiseMap, RangeSet};
 use std::collections::HashMap;
-use std::ops::Range;
 
 advent_of_code::solution!(5);
 
-#[derive(Debug)]
-struct Lut {
-    destination_start: u64,
-    source_start: u64,
-    length: u64,
-}
-
-impl Lut {
-    fn source_range(&self) -> Range<u64> {
-        self.source_start..(self.source_start + self.length)
-    }
-}
-
 #[derive(Debug)]
 struct Converter {
-    luts: Vec<Lut>,
+    lut: PiecewiseMap<u64>,
     destination: String,
 }
 
 impl Converter {
     fn convert(&self, source: &u64) -> u64 {
-        let lut = self.luts.iter()
-            .find(|it| it.source_range().contains(source));
-        if lut.is_none() {
-            return *source;
-        }
-        lut.unwrap().destination_start + (source - lut.unwrap().source_start)
-    }
-
-    fn convert_ranges(&self, source: &[Range<u64>]) -> Vec<Range<u64>> {
-        source.iter().flat_map(|it| self.convert_range(it)).collect()
+        self.lut.map(*source)
     }
 
-    fn convert_range(&self, source: &Range<u64>) -> Vec<Range<u64>> {
-        let mut input = vec![source.clone()];
-        let mut result = Vec::new();
-
-        self.luts.iter()
-            .for_each(|it| {
-                let tmp: Vec<_> = std::mem::take(&mut input);
-                tmp.iter().for_each(|range| {
-                    if it.source_range().start <= range.end && range.start <= it.source_range().end {
-                        let start = cmp::max(range.start, it.source_range().start);
-                        let end = cmp::min(range.end, it.source_range().end);
-                        let dest_start = it.destination_start + (start - it.source_start);
-                        let dest_end = dest_start + (end - start);
-                        result.push(dest_start..dest_end);
-                        if range.start < start {
-                            input.push(range.start..start)
-                        }
-                        if end < range.end {
-                            input.push(end..range.end)
-                        }
-                    } else {
-                        input.push(range.clone())
-                    }
-                })
-            });
-
-        result.append(&mut input);
-        result
+    fn convert_ranges(&self, source: &RangeSet<u64>) -> RangeSet<u64> {
+        self.lut.map_set(source)
     }
 
-    fn parse(text: &str) -> (String, Converter) {
-        let re = Regex::new(r"(\w+)-to-(\w+).*").unwrap();
-        let result = re.captures(text.lines().take(1).next().unwrap()).unwrap();
-        let source = result.get(1).unwrap().as_str().to_string();
-        let destination = result.get(2).unwrap().as_str().to_string();
-        let luts = text.lines().dropping(1)
+    fn parse(text: &str) -> Result<(String, Converter), SolutionError> {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or(text);
+        let [source, destination] = scan(header, "{}-to-{} map:")?;
+        let lut = lines
             .map(|line| {
-                let numbers: Vec<_> = line.split_whitespace().map(|it| it.parse::<u64>().unwrap()).collect();
-                Lut { destination_start: numbers[0], source_start: numbers[1], length: numbers[2] }
+                let [destination_start, source_start, length] = scan(line, "{} {} {}")?;
+                let source_start: u64 = parse_field(source_start)?;
+                let length: u64 = parse_field(length)?;
+                Ok((source_start..source_start + length, parse_field(destination_start)?))
             })
-            .collect();
-        (source, Converter { destination, luts })
+            .collect::<Result<_, SolutionError>>()?;
+        Ok((source.to_string(), Converter { destination: destination.to_string(), lut }))
     }
 }
 
-fn parse_input(input: &str) -> (Vec<u64>, HashMap<String, Converter>) {
-    let parts: Vec<_> = input.split("\n\n").collect();
-    let (_, seeds_text) = parts[0].split_once(": ").unwrap();
-    let seeds: Vec<_> = seeds_text.split_whitespace().map(|it| it.parse::<u64>().unwrap()).collect();
-    let converters: HashMap<_, _> = parts.iter().dropping(1)
-        .map(|it| Converter::parse(it))
-        .collect();
-    (seeds, converters)
+fn parse_input(input: &str) -> Result<(Vec<u64>, HashMap<String, Converter>), SolutionError> {
+    let mut blocks = blocks(input);
+    let (_, seeds_text) = key_value(blocks.next().unwrap_or(input))?;
+    let seeds = try_integers(seeds_text).collect::<Result<_, _>>()?;
+    let converters = blocks.map(Converter::parse).collect::<Result<_, _>>()?;
+    Ok((seeds, converters))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (seeds, converters) = parse_input(input);
+pub fn part_one(input: &str) -> Result<Answer, SolutionError> {
+    let (seeds, converters) = parse_input(input).map_err(|e| e.resolve(input))?;
 
-    Some(seeds.iter()
+    seeds.iter()
         .map(|it| convert_to_location(it, &converters))
-        .min().unwrap() as u32)
+        .min()
+        .map(Answer::from)
+        .ok_or_else(|| SolutionError::new("no seeds"))
 }
 
 fn convert_to_location(seed: &u64, converters: &HashMap<String, Converter>) -> u64 {
@@ -114,41 +66,63 @@ fn convert_to_location(seed: &u64, converters: &HashMap<String, Converter>) -> u
     number
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (seeds, converters) = parse_input(input);
-    let result = seeds.chunks(2)
+pub fn part_two(input: &str) -> Result<Answer, SolutionError> {
+    let (seeds, converters) = parse_input(input).map_err(|e| e.resolve(input))?;
+    let pairs = seeds.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        let line = input.lines().next().unwrap_or(input);
+        return Err(SolutionError::in_input(input, line, "expected pairs of seed start and range length"));
+    }
+    let seeds = pairs
         .map(|it| it[0]..(it[0] + it[1]))
-        .map(|it| convert_range_to_location(it, &converters))
-        .min().unwrap();
-
-    Some(result as u32)
+        .collect();
+    convert_ranges_to_location(seeds, &converters)
+        .min()
+        .map(Answer::from)
+        .ok_or_else(|| SolutionError::new("no seeds"))
 }
 
-fn convert_range_to_location(seed: Range<u64>, converters: &HashMap<String, Converter>) -> u64 {
+fn convert_ranges_to_location(seeds: RangeSet<u64>, converters: &HashMap<String, Converter>) -> RangeSet<u64> {
     let mut category = "seed".to_string();
-    let mut number = vec![seed];
+    let mut numbers = seeds;
 
     while category != "location" {
-        number = converters[&category].convert_ranges(&number);
+        numbers = converters[&category].convert_ranges(&numbers);
         category.clone_from(&converters[&category].destination);
     }
 
-    number.iter().map(|it| it.start).min().unwrap()
+    numbers
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::Location;
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(35));
+        assert_eq!(result.unwrap(), 35.into());
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(46));
+        assert_eq!(result.unwrap(), 46.into());
+    }
+
+    #[test]
+    fn test_odd_seed_count() {
+        let error = part_two("seeds: 79 14 55
+
+seed-to-location map:
+50 98 2").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 1, column: 1 }));
+    }
+
+    #[test]
+    fn test_invalid_map() {
+        let error = part_one("seeds: 79 14\n\nseed-to-location map:\n50 98 2\n52 50").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 5, column: 4 }));
     }
 }
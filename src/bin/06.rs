@@ -1,3 +1,5 @@
+use advent_of_code::util::math::quadratic_below_zero;
+use advent_of_code::util::parse::integers;
 use itertools::Itertools;
 
 advent_of_code::solution!(6);
@@ -14,17 +16,13 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 fn extract_values(text: &str) -> Vec<u64> {
-    text
-        .split_once(':').unwrap().1
-        .split_whitespace()
-        .map(|it| it.parse().unwrap())
-        .collect()
+    integers(text).collect()
 }
 
 fn find_max_distance(time: u64, distance: u64) -> u32 {
-    (1..time)
-        .filter(|it| ((time - it) * it) > distance)
-        .count() as u32
+    // holding the button for x ms wins if x * (time - x) > distance, i.e. x² - time * x + distance < 0
+    quadratic_below_zero(1, -(time as i64), distance as i64)
+        .map_or(0, |it| it.count() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -45,6 +43,51 @@ fn extract_value(text: &str) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::property::{Differential, Rng};
+
+    /// Counts the winning button hold times one by one.
+    fn count_wins(time: u64, distance: u64) -> u32 {
+        (0..=time).filter(|hold| hold * (time - hold) > distance).count() as u32
+    }
+
+    fn reference_one(input: &str) -> Option<u32> {
+        let (times, distances) = input.split_once('\n').unwrap();
+        Some(extract_values(times).into_iter().zip(extract_values(distances)).map(|(t, d)| count_wins(t, d)).product())
+    }
+
+    fn reference_two(input: &str) -> Option<u32> {
+        let (times, distances) = input.split_once('\n').unwrap();
+        Some(count_wins(extract_value(times), extract_value(distances)))
+    }
+
+    /// Generates up to `max_races` races, including ones which are impossible or tied at the best hold time.
+    fn races(rng: &mut Rng, max_races: u64) -> String {
+        let races: Vec<(u64, u64)> = (0..rng.between(1..=max_races))
+            .map(|_| {
+                let time = rng.between(0..=60);
+                (time, rng.between(0..=time * time / 4 + 2))
+            })
+            .collect();
+        let row = |values: Vec<u64>| values.iter().map(|it| format!("{it:>4}")).collect::<String>();
+        format!(
+            "Time:    {}\nDistance:{}\n",
+            row(races.iter().map(|it| it.0).collect()),
+            row(races.iter().map(|it| it.1).collect()),
+        )
+    }
+
+    #[test]
+    fn part_one_matches_reference() {
+        Differential::new(DAY, 1, part_one, reference_one).inputs(|rng| races(rng, 4)).check();
+    }
+
+    #[test]
+    fn part_two_matches_reference() {
+        // the races are concatenated, so two of them keep the brute force fast
+        Differential::new(DAY, 2, part_two, reference_two)
+            .inputs(|rng| races(rng, 2))
+            .check();
+    }
 
     #[test]
     fn test_part_one() {
@@ -1,77 +1,93 @@
-use itertools::Itertools;
-use std::collections::HashMap;
+use advent_of_code::template::{Answer, SolutionError};
+use advent_of_code::util::graph::{parse_edges, Graph};
+use advent_of_code::util::math::lcm_all;
 
 advent_of_code::solution!(8);
 
 
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (instructions, route_map) = parse_input(input);
+pub fn part_one(input: &str) -> Result<u32, SolutionError> {
+    let (instructions, network) = parse_input(input)?;
+    let start = network.id(&"AAA").ok_or_else(|| SolutionError::new("missing node AAA"))?;
 
-    Some(count_steps("AAA", instructions, &route_map))
+    Ok(count_steps(start, instructions, &network))
 }
 
-fn parse_input(input: &str) -> (&str, HashMap<&str, (&str, &str)>) {
-    let instructions = input.lines().next().unwrap();
-    let route_map = input.lines()
-        .dropping(2)
-        .map(|line| (line.get(0..=2).unwrap(), (line.get(7..=9).unwrap(), line.get(12..=14).unwrap())))
-        .collect();
-    (instructions, route_map)
+fn parse_input(input: &str) -> Result<(&str, Graph<&str>), SolutionError> {
+    let (instructions, network) = input.split_once('\n')
+        .ok_or_else(|| SolutionError::new("expected instructions followed by the network"))?;
+    let network = parse_edges(network).map_err(|e| e.resolve(input))?;
+    let instructions = instructions.trim();
+    if instructions.is_empty() {
+        return Err(SolutionError::new("expected the instructions in the first line"));
+    }
+    if let Some(node) = network.nodes().find(|it| network.successors(*it).len() != 2) {
+        // the labels are slices of the input, so the error points to the first mention of the node
+        let label = network.label(node);
+        return Err(SolutionError::in_input(input, label, format!("expected node {label} to lead left and right")));
+    }
+    Ok((instructions, network))
 }
 
-fn count_steps(start: &str, instructions: &str, route_map: &HashMap<&str, (&str, &str)>) -> u32 {
+fn count_steps(start: usize, instructions: &str, network: &Graph<&str>) -> u32 {
     let mut steps = 0;
     let mut current = start;
-    while !current.ends_with('Z') {
-        let route = route_map[current];
+    while !network.label(current).ends_with('Z') {
         let index = steps % instructions.len();
         steps += 1;
-        let instruction = instructions.get(index..=index).unwrap();
-        current = if instruction == "L" { route.0 } else { route.1 }
+        let side = if instructions.get(index..=index) == Some("L") { 0 } else { 1 };
+        current = network.successors(current)[side].0;
     }
     steps as u32
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (instructions, route_map) = parse_input(input);
-
-    let result = route_map.keys()
-        .filter(|it| it.ends_with('A'))
-        .map(|it| count_steps(it, instructions, &route_map) as u64)
-        .reduce(lcm)
-        .unwrap();
-
-    Some(result as u32)
-}
+pub fn part_two(input: &str) -> Result<Answer, SolutionError> {
+    let (instructions, network) = parse_input(input)?;
 
-fn lcm(a: u64, b: u64) -> u64 {
-    (a * b) / gcd(a, b)
-}
+    let result = lcm_all(network.nodes()
+        .filter(|it| network.label(*it).ends_with('A'))
+        .map(|it| count_steps(it, instructions, &network) as u64));
 
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 { a } else { gcd(b, a % b) }
+    Ok(result.into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::Location;
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2));
+        assert_eq!(result.unwrap(), 2);
     }
 
     #[test]
     fn test_part_one2() {
         let result = part_one(&advent_of_code::template::read_file_part("examples", DAY, 3));
-        assert_eq!(result, Some(6));
+        assert_eq!(result.unwrap(), 6);
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file_part("examples", DAY, 2));
-        assert_eq!(result, Some(6));
+        assert_eq!(result.unwrap(), 6.into());
+    }
+
+    #[test]
+    fn test_undefined_node() {
+        let error = part_one("LR\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 3, column: 8 }));
+    }
+
+    #[test]
+    fn test_missing_instructions() {
+        assert!(part_two("\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n").is_err());
+    }
+
+    #[test]
+    fn test_invalid_network() {
+        let error = part_one("LR\n\nAAA = (BBB, BBB)\nBBB (ZZZ, ZZZ)\n").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 4, column: 1 }));
     }
 }
@@ -1,59 +1,79 @@
+use advent_of_code::template::Answer;
+use advent_of_code::util::geometry::interior_points;
 use advent_of_code::util::grid2d::Grid2d;
-use advent_of_code::util::position::{Position, EAST, NORTH, SOUTH, WEST};
-use std::collections::{HashSet, VecDeque};
+use advent_of_code::util::image::{self, Image, Palette, Rgb, Svg};
+use advent_of_code::util::position::Direction::{East, North, South, West};
+use advent_of_code::util::position::{Direction, Position};
+use advent_of_code::util::viz::{self, Color, Frame};
 
 advent_of_code::solution!(10);
 
 type Map = Grid2d<char>;
 
-fn movement(tile: char, direction: Position) -> Option<Position> {
+fn movement(tile: char, direction: Direction) -> Option<Direction> {
     match (tile, direction) {
-        ('|', NORTH) => Some(NORTH),
-        ('|', SOUTH) => Some(SOUTH),
-        ('-', EAST) => Some(EAST),
-        ('-', WEST) => Some(WEST),
-        ('L', SOUTH) => Some(EAST),
-        ('L', WEST) => Some(NORTH),
-        ('J', SOUTH) => Some(WEST),
-        ('J', EAST) => Some(NORTH),
-        ('7', NORTH) => Some(WEST),
-        ('7', EAST) => Some(SOUTH),
-        ('F', NORTH) => Some(EAST),
-        ('F', WEST) => Some(SOUTH),
+        ('|', North) => Some(North),
+        ('|', South) => Some(South),
+        ('-', East) => Some(East),
+        ('-', West) => Some(West),
+        ('L', South) => Some(East),
+        ('L', West) => Some(North),
+        ('J', South) => Some(West),
+        ('J', East) => Some(North),
+        ('7', North) => Some(West),
+        ('7', East) => Some(South),
+        ('F', North) => Some(East),
+        ('F', West) => Some(South),
         _ => None,
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (width, height, start, mut map) = parse_map(input);
+pub fn part_one(input: &str) -> Option<Answer> {
+    let (width, height, start, map) = parse_map(input);
 
-    let result = traverse_pipe(&mut map, start, width, height, &|_, _, _, _| {}).len();
-    Some((result / 2) as u32)
+    let pipe = traverse_pipe(&map, start, width, height);
+    viz::show(Frame::new(&map).title("Pipe loop").highlight([start], Color::Red).highlight(pipe.iter().copied(), Color::Yellow));
+    if viz::enabled() {
+        // the pictures are optional, failing to write them must not cost the answer
+        if let Err(error) = export_loop(&map, &pipe) {
+            eprintln!("could not write the pictures of the loop: {error}");
+        }
+    }
+
+    Some((pipe.len() / 2).into())
 }
 
-fn traverse_pipe(map: &mut Map, start: Position, width: usize, height: usize, pre_move: &dyn Fn(&Position, &Position, &Position, &mut Map)) -> HashSet<Position> {
-    let mut current = start.neighbors(Position::moves(false, false))
+/// Returns the positions of the pipe loop in the order they are traversed, starting with `start`.
+fn traverse_pipe(map: &Map, start: Position, width: usize, height: usize) -> Vec<Position> {
+    let mut direction = Direction::all()
         .into_iter()
-        .filter(|it| it.is_safe(width as i64, height as i64))
-        .find(|&pos| {
-            let dir = pos - start;
-            movement(map.get(&pos), dir).is_some()
-        }).unwrap();
-
-    let mut direction = current - start;
-    let mut pipe = HashSet::new();
-    pipe.insert(start);
+        .filter(|&dir| (start + dir).is_safe(width as i64, height as i64))
+        .find(|&dir| movement(map.get(&(start + dir)), dir).is_some())
+        .unwrap();
+
+    let mut current = start + direction;
+    let mut pipe = vec![start];
     while current != start {
-        pipe.insert(current);
+        pipe.push(current);
         let tile = map.get(&current);
-        let next_direction = movement(tile, direction).expect("Invalid movement");
-        pre_move(&current, &direction, &next_direction, map);
-        direction = next_direction;
+        direction = movement(tile, direction).expect("Invalid movement");
         current = current + direction;
     }
     pipe
 }
 
+/// Writes the loop on top of the pipes as PNG, and as SVG line through the tiles of the loop.
+fn export_loop(map: &Map, pipe: &[Position]) -> std::io::Result<()> {
+    let mut picture = Image::of_grid(map, &Palette::new(Rgb(180, 180, 180)).with('.', Rgb::WHITE), 4);
+    picture.draw_path(pipe, Rgb(230, 160, 0));
+    picture.fill(&pipe[0], Rgb(200, 0, 0));
+    image::write(DAY, "loop.png", picture.to_png())?;
+    let closed = [pipe, &pipe[..1]].concat();
+    let outline = Svg::new(10).grid(map, &Palette::new(Rgb::WHITE).with('.', Rgb(230, 230, 230))).path(&closed, Rgb(230, 160, 0));
+    image::write(DAY, "loop.svg", outline.to_string())?;
+    Ok(())
+}
+
 fn parse_map(input: &str) -> (usize, usize, Position, Map) {
     let width = input.lines().next().unwrap().len();
     let height = input.lines().count();
@@ -65,67 +85,11 @@ fn parse_map(input: &str) -> (usize, usize, Position, Map) {
     (width, height, start, map)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (width, height, start, mut map) = parse_map(input);
-
-    let pipe = traverse_pipe(&mut map, start, width, height, &|_, _, _, _| {});
-    for y in 0..height {
-        for x in 0..width {
-            let pos = Position::from((x, y));
-            if !pipe.contains(&pos) {
-                map.set(&pos, '.');
-            }
-        }
-    }
-
-    let empty_corner = vec![
-        Position::from((0, 0)),
-        Position::from((0, height - 1)),
-        Position::from((width - 1, 0)),
-        Position::from((width, height))
-    ]
-        .into_iter().find(|it| map.get(it) == '.')
-        .unwrap();
-
-    let corners = HashSet::from(['7', 'L', 'J', 'F']);
-    traverse_pipe(&mut map, start, width, height, &|pos, dir, next_dir, map| {
-        fill_area(map, *pos + marking_direction(*dir), 'O', width as i64, height as i64);
-        if corners.contains(&map.get(pos)) {
-            fill_area(map, *pos + marking_direction(*next_dir), 'O', width as i64, height as i64);
-        }
-    });
-
-    let target = if map.get(&empty_corner) == 'O' { '.' } else { 'O' };
-    Some(map.find_all(target).len() as u32)
-}
-
-fn marking_direction(dir: Position) -> Position {
-    match dir {
-        NORTH => WEST,
-        SOUTH => EAST,
-        WEST => SOUTH,
-        EAST => NORTH,
-        _ => panic!("Invalid direction to mark")
-    }
-}
-
-fn fill_area(map: &mut Map, pos: Position, c: char, width: i64, height: i64) {
-    if !pos.is_safe(width, height) {
-        return;
-    }
+pub fn part_two(input: &str) -> Option<Answer> {
+    let (width, height, start, map) = parse_map(input);
 
-    let mut queue = VecDeque::<Position>::new();
-    queue.push_back(pos);
-    while !queue.is_empty() {
-        let next = queue.pop_front().unwrap();
-        if !next.is_safe(width, height) {
-            continue;
-        }
-        if map.get(&next) == '.' {
-            map.set(&next, c);
-            queue.extend(next.neighbors(Position::moves(false, false)));
-        }
-    }
+    let pipe = traverse_pipe(&map, start, width, height);
+    Some(interior_points(&pipe).into())
 }
 
 #[cfg(test)]
@@ -135,24 +99,24 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4));
+        assert_eq!(result, Some(4.into()));
     }
 
     #[test]
     fn test_part_one2() {
         let result = part_one(&advent_of_code::template::read_file_part("examples", DAY, 2));
-        assert_eq!(result, Some(8));
+        assert_eq!(result, Some(8.into()));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file_part("examples", DAY, 3));
-        assert_eq!(result, Some(4));
+        assert_eq!(result, Some(4.into()));
     }
 
     #[test]
     fn test_part_two2() {
         let result = part_two(&advent_of_code::template::read_file_part("examples", DAY, 4));
-        assert_eq!(result, Some(8));
+        assert_eq!(result, Some(8.into()));
     }
 }
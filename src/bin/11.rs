@@ -1,16 +1,17 @@
+use advent_of_code::template::Answer;
 use advent_of_code::util::position::Position;
 use itertools::Itertools;
 
 advent_of_code::solution!(11);
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<Answer> {
     let galaxies = find_galaxies(input);
     let expansions_x = find_expansions(&galaxies, 2, &|it| it.x);
     let expansions_y = find_expansions(&galaxies, 2, &|it| it.y);
     let expanded_galaxies = expand(galaxies, expansions_x, expansions_y);
     let result = calculate_distances(&expanded_galaxies);
 
-    Some(result as u32)
+    Some(result.into())
 }
 
 fn calculate_distances(expanded_galaxies: &[Position]) -> u64 {
@@ -78,11 +79,51 @@ pub fn part_two(input: &str) -> Option<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::property::{Differential, Rng};
+
+    /// Sums the distances of all pairs of galaxies, with every empty row and column between them counting `factor` times.
+    fn sum_distances(input: &str, factor: u64) -> u64 {
+        let grid: Vec<Vec<char>> = input.lines().map(|it| it.chars().collect()).collect();
+        let empty_rows: Vec<bool> = grid.iter().map(|row| !row.contains(&'#')).collect();
+        let empty_columns: Vec<bool> = (0..grid[0].len()).map(|x| grid.iter().all(|row| row[x] != '#')).collect();
+        let galaxies: Vec<(usize, usize)> = (0..grid.len())
+            .flat_map(|y| (0..grid[y].len()).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid[y][x] == '#')
+            .collect();
+        let span = |a: usize, b: usize, empty: &[bool]| {
+            (a.min(b)..a.max(b)).map(|it| if empty[it] { factor } else { 1 }).sum::<u64>()
+        };
+        let mut total = 0;
+        for (i, a) in galaxies.iter().enumerate() {
+            for b in &galaxies[i + 1..] {
+                total += span(a.0, b.0, &empty_columns) + span(a.1, b.1, &empty_rows);
+            }
+        }
+        total
+    }
+
+    /// Generates a small image with at least one galaxy, like every puzzle input.
+    fn image(rng: &mut Rng) -> String {
+        let (width, height) = (rng.between(1..=8) as usize, rng.between(1..=8) as usize);
+        let mut cells: Vec<char> = (0..width * height).map(|_| if rng.chance(0.2) { '#' } else { '.' }).collect();
+        cells[rng.between(0..=(width * height) as u64 - 1) as usize] = '#';
+        cells.chunks(width).map(|row| row.iter().collect::<String>() + "\n").collect()
+    }
+
+    #[test]
+    fn part_one_matches_reference() {
+        Differential::new(DAY, 1, part_one, |input: &str| Some(sum_distances(input, 2))).inputs(image).check();
+    }
+
+    #[test]
+    fn part_two_matches_reference() {
+        Differential::new(DAY, 2, part_two, |input: &str| Some(sum_distances(input, 1000000))).inputs(image).check();
+    }
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(374));
+        assert_eq!(result, Some(374.into()));
     }
 
     #[test]
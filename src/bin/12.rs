@@ -1,55 +1,58 @@
+use advent_of_code::template::{Answer, Memo};
 use itertools::Itertools;
-use memoize::memoize;
 
 advent_of_code::solution!(12);
 
-pub fn part_one(input: &str) -> Option<u32> {
+type ArrangementMemo<'a> = Memo<(&'a [u8], &'a [usize]), u64>;
+
+pub fn part_one(input: &str) -> Option<Answer> {
     let result: u64 = input
         .lines()
         .map(parse_line)
-        .map(|(springs, damage)| count_arrangements(springs.to_string(), damage))
+        .map(|(springs, damage)| count_arrangements(springs.as_bytes(), &damage, &mut Memo::new()))
         .sum();
 
-    Some(result as u32)
+    Some(result.into())
 }
 
-fn parse_line(line: &str) -> (&str, Vec<i32>) {
+fn parse_line(line: &str) -> (&str, Vec<usize>) {
     let (springs, damage_text) = line.split_once(" ").unwrap();
     let damage = damage_text.split(",").map(|it| it.parse().unwrap()).collect();
     (springs, damage)
 }
 
-#[memoize]
-fn count_arrangements(springs: String, damage: Vec<i32>) -> u64 {
+fn count_arrangements<'a>(springs: &'a [u8], damage: &'a [usize], memo: &mut ArrangementMemo<'a>) -> u64 {
     if springs.is_empty() {
         return if damage.is_empty() { 1 } else { 0 };
     }
 
-    match springs.chars().next().unwrap() {
-        '.' => count_arrangements(springs.trim_start_matches('.').to_string(), damage),
-        '?' => {
-            let next_springs = springs.get(1..).unwrap().to_string();
-            let next_springs2 = String::from("#") + next_springs.as_str();
-            count_arrangements(next_springs, damage.clone())
-                + count_arrangements(next_springs2, damage)
-        }
-        '#' if damage.is_empty() => 0,
-        '#' if !damage.is_empty() => {
-            let next_damage = damage[0] as usize;
-            let remaining_damage = damage.into_iter().dropping(1).collect_vec();
-            if next_damage <= springs.len() && springs.chars().take(next_damage).all(|c| c != '.') {
-                if next_damage == springs.len() {
-                    if remaining_damage.is_empty() { 1 } else { 0 }
-                } else if springs.chars().nth(next_damage) == Some('#') {
-                    0
-                } else {
-                    count_arrangements(springs.get(next_damage + 1..).unwrap().to_string(), remaining_damage)
-                }
-            } else {
-                0
-            }
+    memo.get_or_compute(&(springs, damage), |memo| match springs[0] {
+        b'.' => {
+            let start = springs.iter().position(|it| *it != b'.').unwrap_or(springs.len());
+            count_arrangements(&springs[start..], damage, memo)
         }
+        b'?' => count_arrangements(&springs[1..], damage, memo) + count_damaged(springs, damage, memo),
+        b'#' => count_damaged(springs, damage, memo),
         _ => panic!("Unexpected char in springs text")
+    })
+}
+
+/// Counts the arrangements in which the first spring is damaged.
+fn count_damaged<'a>(springs: &'a [u8], damage: &'a [usize], memo: &mut ArrangementMemo<'a>) -> u64 {
+    let Some((&next_damage, remaining_damage)) = damage.split_first() else {
+        return 0;
+    };
+    // an empty group cannot start with the damaged spring
+    if next_damage > 0 && next_damage <= springs.len() && springs[1..next_damage].iter().all(|c| *c != b'.') {
+        if next_damage == springs.len() {
+            if remaining_damage.is_empty() { 1 } else { 0 }
+        } else if springs[next_damage] == b'#' {
+            0
+        } else {
+            count_arrangements(&springs[next_damage + 1..], remaining_damage, memo)
+        }
+    } else {
+        0
     }
 }
 
@@ -58,13 +61,13 @@ pub fn part_two(input: &str) -> Option<u64> {
         .lines()
         .map(parse_line)
         .map(unfold)
-        .map(|(springs, damage)| count_arrangements(springs, damage))
+        .map(|(springs, damage)| count_arrangements(springs.as_bytes(), &damage, &mut Memo::new()))
         .sum();
 
     Some(result)
 }
 
-fn unfold(input: (&str, Vec<i32>)) -> (String, Vec<i32>) {
+fn unfold(input: (&str, Vec<usize>)) -> (String, Vec<usize>) {
     let springs = (0..=4).map(|_| input.0)
         .join("?");
     let damage = (0..=4).flat_map(|_| input.1.clone()).collect_vec();
@@ -78,7 +81,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(21));
+        assert_eq!(result, Some(21.into()));
     }
 
     #[test]
@@ -86,4 +89,9 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(525152));
     }
+
+    #[test]
+    fn test_empty_group() {
+        assert_eq!(part_one("#.? 0,1"), Some(0.into()));
+    }
 }
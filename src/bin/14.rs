@@ -1,50 +1,38 @@
+use advent_of_code::util::automaton::Simulation;
 use advent_of_code::util::grid2d::Grid2d;
+use advent_of_code::util::image::{self, Image, Palette, Rgb};
 use advent_of_code::util::position::{Position, EAST, NORTH, SOUTH, WEST};
-use std::collections::hash_map::Entry::Vacant;
-use std::collections::HashMap;
-use std::hash::{DefaultHasher, Hash, Hasher};
+use advent_of_code::util::viz;
 
 advent_of_code::solution!(14);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut grid = Grid2d::of_lines(input);
-    tilt_grid(&mut grid, &NORTH);
+    let grid = Grid2d::of_lines(input);
+    let mut simulation = tilt(Simulation::new(grid), NORTH);
+    simulation.step();
+    advent_of_code::trace_grid!("tilted north", simulation.grid());
 
-    Some(score(&grid))
+    Some(score(simulation.grid()))
 }
 
-fn progressions(grid: &Grid2d<char>) -> HashMap<Position, Vec<Position>> {
-    let (width, height) = grid.size();
-    HashMap::from([
-        (NORTH, (0..height).flat_map(|y| {
-            (0..width).map(move |x| Position::from((x, y)))
-        }).collect()),
-        (WEST, (0..width).flat_map(|x| {
-            (0..height).map(move |y| Position::from((x, y)))
-        }).collect()),
-        (SOUTH, (0..height).rev().flat_map(|y| {
-            (0..width).map(move |x| Position::from((x, y)))
-        }).collect()),
-        (EAST, (0..width).rev().flat_map(|x| {
-            (0..height).map(move |y| Position::from((x, y)))
-        }).collect()),
-    ])
+/// Returns the positions ordered so that the ones furthest in `direction` come first.
+fn progression(grid: &Grid2d<char>, direction: Position) -> Vec<Position> {
+    let mut positions: Vec<Position> = grid.positions().collect();
+    positions.sort_by_key(|it| -(it.x * direction.x + it.y * direction.y));
+    positions
 }
 
-fn tilt_grid(grid: &mut Grid2d<char>, direction: &Position) {
-    let stones: Vec<Position> = progressions(grid)[direction].clone().into_iter()
-        .filter(|it| grid.get(it) == 'O')
-        .collect();
-    stones.iter().for_each(|it| tilt_rock(grid, it, direction))
-}
-
-fn tilt_rock(grid: &mut Grid2d<char>, position: &Position, direction: &Position) {
-    let mut current = *position;
-    let (width, height) = grid.size();
-    while (current + *direction).is_safe(width as i64, height as i64) && grid.get(&(&current + direction)) == '.' {
-        grid.swap(&current, &(&current + direction));
-        current = &current + direction;
-    }
+/// Adds a phase which rolls every rock in `direction` until it hits another rock, a cube or the edge.
+/// The rocks furthest in `direction` roll first, so the ones behind them pile up.
+fn tilt(simulation: Simulation<char>, direction: Position) -> Simulation<char> {
+    let order = progression(simulation.grid(), direction);
+    simulation.in_place(order, move |cell| {
+        if cell.value() == 'O' {
+            while cell.get(direction) == Some('.') {
+                cell.move_by(direction);
+            }
+        }
+    })
 }
 
 fn score(grid: &Grid2d<char>) -> u32 {
@@ -55,41 +43,30 @@ fn score(grid: &Grid2d<char>) -> u32 {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut grid = Grid2d::of_lines(input);
-    Some(solve_cycles(&mut grid, 1_000_000_000))
-}
-
-fn solve_cycles(grid: &mut Grid2d<char>, goal: u32) -> u32 {
-    let mut seen = HashMap::<u64, u32>::new();
-    let mut cycle = 1;
-    while cycle <= goal {
-        tilt_grid(grid, &NORTH);
-        tilt_grid(grid, &WEST);
-        tilt_grid(grid, &SOUTH);
-        tilt_grid(grid, &EAST);
-        let state = hash(grid);
-        if let Vacant(e) = seen.entry(state) {
-            e.insert(cycle);
-            cycle += 1;
-        } else {
-            let cycle_length = cycle - seen[&state];
-            let remaining_cycles = (goal - cycle) % cycle_length;
-            (0..remaining_cycles).for_each(|_| {
-                tilt_grid(grid, &NORTH);
-                tilt_grid(grid, &WEST);
-                tilt_grid(grid, &SOUTH);
-                tilt_grid(grid, &EAST);
-            });
-            return score(grid);
+    let grid = Grid2d::of_lines(input);
+    let mut simulation = [NORTH, WEST, SOUTH, EAST].into_iter().fold(Simulation::new(grid), tilt);
+    if viz::enabled() {
+        simulation = simulation.record();
+    }
+    simulation.skip_to(1_000_000_000);
+    if viz::enabled() {
+        if let Err(error) = export_rocks(&simulation) {
+            eprintln!("could not write the pictures of the rocks: {error}");
         }
     }
-    score(grid)
+    Some(score(simulation.grid()))
 }
 
-fn hash(grid: &Grid2d<char>) -> u64 {
-    let mut s = DefaultHasher::new();
-    grid.hash(&mut s);
-    s.finish()
+/// Writes the final rock field as PNG and the simulated spin cycles as animated GIF.
+fn export_rocks(simulation: &Simulation<char>) -> std::io::Result<()> {
+    let palette = Palette::new(Rgb(240, 235, 220)).with('O', Rgb(200, 90, 30)).with('#', Rgb(70, 70, 80));
+    let scale = 4;
+    image::write(DAY, "rocks.png", Image::of_grid(simulation.grid(), &palette, scale).to_png())?;
+    let frames: Vec<Image> = simulation.frames().iter().map(|it| Image::of_grid(it, &palette, scale)).collect();
+    if let Some(gif) = image::to_gif(&frames, 10) {
+        image::write(DAY, "cycles.gif", gif)?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,70 +1,48 @@
 use advent_of_code::util::grid2d::Grid2d;
-use advent_of_code::util::position::{Position, EAST, NORTH, SOUTH, WEST};
-use itertools::Itertools;
-use std::collections::{HashSet, VecDeque};
+use advent_of_code::util::position::Direction::{East, North, South, West};
+use advent_of_code::util::position::{Direction, Position};
+use advent_of_code::util::search::bfs_by;
+use advent_of_code::util::viz::{self, Color, Frame};
+use std::collections::HashSet;
 
 advent_of_code::solution!(16);
 
 pub fn part_one(input: &str) -> Option<u32> {
     let grid = Grid2d::of_lines(input);
-    let result = energize(Position::from((0, 0)), EAST, &grid);
-    Some(result)
+    let energized = energize(Position::from((0, 0)), East, &grid);
+    viz::show(Frame::new(&grid).title("Energized tiles").highlight(energized.iter().copied(), Color::Cyan));
+
+    Some(energized.len() as u32)
 }
 
-fn movement(tile: char, direction: Position) -> Vec<Position> {
+fn movement(tile: char, direction: Direction) -> Vec<Direction> {
     match (tile, direction) {
-        ('|', NORTH | SOUTH) => vec![direction],
-        ('|', EAST | WEST) => vec![NORTH, SOUTH],
-        ('-', EAST | WEST) => vec![direction],
-        ('-', NORTH | SOUTH) => vec![WEST, EAST],
-        ('/', EAST) => vec![NORTH],
-        ('/', NORTH) => vec![EAST],
-        ('/', WEST) => vec![SOUTH],
-        ('/', SOUTH) => vec![WEST],
-        ('\\', EAST) => vec![SOUTH],
-        ('\\', SOUTH) => vec![EAST],
-        ('\\', WEST) => vec![NORTH],
-        ('\\', NORTH) => vec![WEST],
+        ('|', North | South) => vec![direction],
+        ('|', East | West) => vec![North, South],
+        ('-', East | West) => vec![direction],
+        ('-', North | South) => vec![West, East],
+        ('/', East) => vec![North],
+        ('/', North) => vec![East],
+        ('/', West) => vec![South],
+        ('/', South) => vec![West],
+        ('\\', East) => vec![South],
+        ('\\', South) => vec![East],
+        ('\\', West) => vec![North],
+        ('\\', North) => vec![West],
         ('.', _) => vec![direction],
         _ => vec![],
     }
 }
 
-fn energize(start: Position, start_direction: Position, grid: &Grid2d<char>) -> u32 {
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-    visited.insert((start, start_direction));
-    queue.push_back((start, start_direction));
-
-    while !queue.is_empty() {
-        let (current, direction) = queue.pop_front().unwrap();
-        let next_directions = movement(grid.get(&current), direction);
-        next_directions.iter()
-            .for_each(|&dir| {
-                let next_pos = current + dir;
-                if !visited.contains(&(next_pos, dir)) && next_pos.is_within(grid) {
-                    queue.push_back((next_pos, dir));
-                    visited.insert((next_pos, dir));
-                }
-            })
-    }
-
-    visited.iter().map(|(pos, _)| *pos).unique().count() as u32
-}
-
-#[allow(unused)]
-fn print_visited(grid: &Grid2d<char>, visited: &HashSet<Position>) {
-    grid.for_each(|x, y, c| {
-        if x == 0 {
-            println!()
-        }
-        if visited.contains(&Position::at(x as i64, y as i64)) {
-            print!("#");
-        } else {
-            print!(".");
-        }
+/// Returns the tiles a beam entering at `start` passes.
+fn energize(start: Position, start_direction: Direction, grid: &Grid2d<char>) -> HashSet<Position> {
+    let search = bfs_by([(start, start_direction)], |&(current, direction)| {
+        movement(grid.get(&current), direction).into_iter()
+            .map(move |dir| (current + dir, dir))
+            .filter(move |(next_pos, _)| next_pos.is_within(grid))
     });
-    print!("\n\n");
+
+    search.distances().keys().map(|(pos, _)| *pos).collect()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -72,13 +50,13 @@ pub fn part_two(input: &str) -> Option<u32> {
     let (width, height) = grid.size();
 
     let mut starts = Vec::new();
-    starts.extend((0..width).map(|it| (Position::from((it, 0)), SOUTH)));
-    starts.extend((0..width).map(|it| (Position::from((it, height - 1)), NORTH)));
-    starts.extend((0..height).map(|it| (Position::from((0, it)), EAST)));
-    starts.extend((0..height).map(|it| (Position::from((width - 1, it)), WEST)));
+    starts.extend((0..width).map(|it| (Position::from((it, 0)), South)));
+    starts.extend((0..width).map(|it| (Position::from((it, height - 1)), North)));
+    starts.extend((0..height).map(|it| (Position::from((0, it)), East)));
+    starts.extend((0..height).map(|it| (Position::from((width - 1, it)), West)));
 
-    let result = starts.iter().map(|(start, dir)| energize(*start, *dir, &grid)).max()?;
-    Some(result)
+    let result = starts.iter().map(|(start, dir)| energize(*start, *dir, &grid).len()).max()?;
+    Some(result as u32)
 }
 
 #[cfg(test)]
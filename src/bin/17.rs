@@ -1,6 +1,7 @@
 use advent_of_code::util::grid2d::Grid2d;
-use advent_of_code::util::position::{Position, EAST, NORTH, SOUTH, WEST};
-use pathfinding::prelude::dijkstra;
+use advent_of_code::util::position::{Direction, Position};
+use advent_of_code::util::search::{dijkstra_all_by, dijkstra_by};
+use advent_of_code::util::viz::{self, Color, Frame};
 
 advent_of_code::solution!(17);
 
@@ -14,32 +15,40 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(heat_loss)
 }
 
-fn find_heat_loss(grid: &Grid2d<char>, min_steps: u32, filter: &dyn Fn(Position, Position, u32) -> bool) -> u32 {
+fn find_heat_loss(grid: &Grid2d<char>, min_steps: u32, filter: &dyn Fn(Direction, Direction, u32) -> bool) -> u32 {
     let (width, height) = grid.size();
     let goal = Position::from((width - 1, height - 1));
-    let start = State { position: Position::from((0, 0)), direction: EAST, steps: 1 };
-    let result = dijkstra(&start, |state| state.successor(grid, filter), |state| state.position == goal && state.steps > min_steps);
-    let loss1 = result.map(|(_, it)| it);
-    let start = State { position: Position::from((0, 0)), direction: SOUTH, steps: 1 };
-    let result = dijkstra(&start, |state| state.successor(grid, filter), |state| state.position == goal && state.steps > min_steps);
-    let loss2 = result.map(|(_, it)| it);
-    loss1.min(loss2).unwrap()
+    let starts = [Direction::East, Direction::South].map(|direction| State { position: Position::from((0, 0)), direction, steps: 1 });
+    let is_goal = |state: &State| state.position == goal && state.steps > min_steps;
+    let result = dijkstra_by(starts, |state| state.successor(grid, filter), is_goal);
+
+    if viz::enabled() {
+        // the search above does not keep the predecessors, so the path is searched again for the visualization
+        let search = dijkstra_all_by(starts, |state| state.successor(grid, filter));
+        let path = search.distances().iter()
+            .filter(|(state, _)| is_goal(state))
+            .min_by_key(|(_, distance)| **distance)
+            .and_then(|(state, _)| search.path_to(state))
+            .unwrap_or_default();
+        viz::show(Frame::new(grid).title("Path with the least heat loss").highlight(path.iter().map(|it| it.position), Color::Red));
+    }
+    result.map(|(_, it)| it as u32).unwrap()
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Copy)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
 struct State {
     position: Position,
-    direction: Position,
+    direction: Direction,
     steps: u32,
 }
 
 impl State {
-    fn successor<F>(&self, grid: &Grid2d<char>, filter: F) -> Vec<(State, u32)>
+    fn successor<F>(&self, grid: &Grid2d<char>, filter: F) -> Vec<(State, u64)>
     where
-        F: Fn(Position, Position, u32) -> bool,
+        F: Fn(Direction, Direction, u32) -> bool,
     {
         let mut successors = Vec::new();
-        let directions = directions(&self.direction);
+        let directions = [self.direction, self.direction.turn_left(), self.direction.turn_right()];
         for direction in directions {
             if filter(direction, self.direction, self.steps) {
                 continue;
@@ -47,14 +56,14 @@ impl State {
             let next_position = self.position + direction;
             if next_position.is_within(grid) {
                 let next_state = self.next(direction);
-                let next_cost = grid.get(&next_state.position).to_digit(10).unwrap();
+                let next_cost = grid.get(&next_state.position).to_digit(10).unwrap() as u64;
                 successors.push((next_state, next_cost));
             }
         }
         successors
     }
 
-    fn next(&self, direction: Position) -> State {
+    fn next(&self, direction: Direction) -> State {
         State {
             position: self.position + direction,
             direction,
@@ -63,16 +72,6 @@ impl State {
     }
 }
 
-fn directions(direction: &Position) -> Vec<Position> {
-    match *direction {
-        NORTH => vec![NORTH, EAST, WEST],
-        EAST => vec![EAST, NORTH, SOUTH],
-        SOUTH => vec![SOUTH, EAST, WEST],
-        WEST => vec![WEST, NORTH, SOUTH],
-        _ => panic!("Illegal input direction")
-    }
-}
-
 pub fn part_two(input: &str) -> Option<u32> {
     let grid = Grid2d::of_lines(input);
     let heat_loss = find_heat_loss(
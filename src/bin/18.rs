@@ -1,91 +1,103 @@
-use advent_of_code::util::position::{Position, EAST, NORTH, SOUTH, WEST};
-use itertools::Itertools;
+use advent_of_code::template::{Answer, SolutionError};
+use advent_of_code::util::geometry::lattice_points;
+use advent_of_code::util::image::{self, Rgb, Svg};
+use advent_of_code::util::position::{Direction, Position};
+use advent_of_code::util::viz;
 
 advent_of_code::solution!(18);
 
 struct Step {
-    direction: Position,
+    direction: Direction,
     steps: u64,
 }
 
 impl Step {
-    fn parse(line: &str) -> Step {
+    fn parse(input: &str, line: &str) -> Result<Step, SolutionError> {
         let mut parts = line.split_whitespace();
-        let direction = match parts.next().unwrap() {
-            "U" => NORTH,
-            "R" => EAST,
-            "D" => SOUTH,
-            "L" => WEST,
-            _ => panic!("Illegal direction"),
-        };
-        let steps: u64 = parts.next().unwrap().parse().unwrap();
+        let direction_text = parts.next().ok_or_else(|| SolutionError::in_input(input, line, "missing direction"))?;
+        let direction = direction_text.parse().map_err(|e| SolutionError::from(e).at(input, direction_text))?;
+        let steps_text = parts.next().ok_or_else(|| SolutionError::in_input(input, line, "missing step count"))?;
+        let steps = steps_text.parse().map_err(|e| SolutionError::from(e).at(input, steps_text))?;
 
-        Step { direction, steps }
+        Ok(Step { direction, steps })
     }
 
-    fn decode(line: &str) -> Step {
-        let hexcode = line.split_whitespace().last().unwrap();
-        let steps = hexcode.get(2..=6).unwrap();
-        let steps = u64::from_str_radix(steps, 16).unwrap();
-        let direction = match hexcode.get(7..=7).unwrap() {
-            "3" => NORTH,
-            "0" => EAST,
-            "1" => SOUTH,
-            "2" => WEST,
-            _ => panic!("Illegal direction"),
+    fn decode(input: &str, line: &str) -> Result<Step, SolutionError> {
+        let hexcode = line.split_whitespace().last().ok_or_else(|| SolutionError::in_input(input, line, "missing color code"))?;
+        let (Some(steps), Some(direction)) = (hexcode.get(2..=6), hexcode.get(7..=7)) else {
+            return Err(SolutionError::in_input(input, hexcode, "expected a color code like (#70c710)"));
+        };
+        let steps = u64::from_str_radix(steps, 16).map_err(|e| SolutionError::from(e).at(input, steps))?;
+        let direction = match direction {
+            "3" => Direction::North,
+            "0" => Direction::East,
+            "1" => Direction::South,
+            "2" => Direction::West,
+            _ => return Err(SolutionError::in_input(input, direction, "illegal direction, expected 0 to 3")),
         };
-        Step { direction, steps }
+        Ok(Step { direction, steps })
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let steps = input.lines().map(Step::parse).collect_vec();
-    let result = calculate_area(steps);
-    Some(result as u32)
+pub fn part_one(input: &str) -> Result<Answer, SolutionError> {
+    let steps = input.lines().map(|line| Step::parse(input, line)).collect::<Result<Vec<_>, _>>()?;
+    let result = calculate_area(steps, "lagoon-1.svg");
+    Ok(result.into())
 }
 
-fn calculate_area(steps: Vec<Step>) -> u64 {
+/// Returns the area of the lagoon, and draws its outline to `picture` if the visualization is enabled.
+fn calculate_area(steps: Vec<Step>, picture: &str) -> u64 {
     let mut current = Position { x: 0, y: 0 };
-    let mut visited = vec![current];
+    let mut vertices = vec![current];
 
     steps.iter().for_each(|step| {
-        let new_position = current + step.direction * step.steps as i64;
-        visited.push(current);
-        current = new_position;
+        current = current + Position::from(step.direction) * step.steps as i64;
+        vertices.push(current);
     });
-    visited.push(Position { x: 0, y: 0 });
-
-    // use shoelace formula to calculate the are
-    // https://en.wikipedia.org/wiki/Shoelace_formula#Shoelace_formula
-    let area = visited.windows(2)
-        .fold(0, |acc, p| acc + p[0].x * p[1].y - p[1].x * p[0].y) as u64 / 2;
-    let perimeter = steps.iter().fold(0, |acc, step: &Step| acc + step.steps);
+    if viz::enabled() {
+        let lagoon = Svg::new(1).polygon(&vertices, Rgb(120, 60, 20), Some(Rgb(40, 120, 200)));
+        if let Err(error) = image::write(DAY, picture, lagoon.to_string()) {
+            eprintln!("could not write the picture of the lagoon: {error}");
+        }
+    }
 
-    // use pick's theorem to get the result (A = i + b/2 - 1)
-    // we want to calculate i + b = A + b/2 + 1
-    area + perimeter / 2 + 1
+    // the trench covers the interior and the boundary of the dug path
+    lattice_points(&vertices)
 }
 
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let steps = input.lines().map(Step::decode).collect_vec();
-    let result = calculate_area(steps);
-    Some(result)
+pub fn part_two(input: &str) -> Result<u64, SolutionError> {
+    let steps = input.lines().map(|line| Step::decode(input, line)).collect::<Result<Vec<_>, _>>()?;
+    let result = calculate_area(steps, "lagoon-2.svg");
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::Location;
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(62));
+        assert_eq!(result.unwrap(), 62.into());
+    }
+
+    #[test]
+    fn test_part_one_invalid() {
+        let error = part_one("R 6 (#70c710)\nD x (#0dc571)").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 2, column: 3 }));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(952408144115));
+        assert_eq!(result.unwrap(), 952408144115);
+    }
+
+    #[test]
+    fn test_part_two_invalid() {
+        let error = part_two("R 6 (#70c710)\nD 5 (#0dc575)").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 2, column: 12 }));
     }
 }
@@ -0,0 +1,49 @@
+use advent_of_code::template::SolutionError;
+use advent_of_code::util::parse::{blocks, map_lines};
+use advent_of_code::util::ranges::RangeSet;
+use advent_of_code::util::rules::{parse_record, Program, Record, Region};
+
+advent_of_code::solution!(19);
+
+const CATEGORIES: [&str; 4] = ["x", "m", "a", "s"];
+
+fn parse_input(input: &str) -> Result<(Program<'_>, Vec<Record<'_>>), SolutionError> {
+    let mut blocks = blocks(input);
+    let workflows = Program::parse(blocks.next().unwrap_or_default())?;
+    let parts = map_lines(blocks.next().unwrap_or_default(), parse_record)?;
+    Ok((workflows, parts))
+}
+
+pub fn part_one(input: &str) -> Result<u64, SolutionError> {
+    let (workflows, parts) = parse_input(input)?;
+    let mut result = 0;
+    for part in parts {
+        if workflows.evaluate("in", &part)? {
+            result += part.values().sum::<u64>();
+        }
+    }
+    Ok(result)
+}
+
+pub fn part_two(input: &str) -> Result<u64, SolutionError> {
+    let (workflows, _) = parse_input(input)?;
+    let ratings: Region = CATEGORIES.iter().map(|it| (*it, RangeSet::from(1..4001))).collect();
+    workflows.count_accepted("in", ratings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 19114);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 167409079868000);
+    }
+}
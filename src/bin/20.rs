@@ -0,0 +1,67 @@
+use advent_of_code::template::SolutionError;
+use advent_of_code::util::circuit::{Circuit, Pulse};
+use advent_of_code::util::math::lcm_all;
+
+advent_of_code::solution!(20);
+
+/// The inputs of the conjunction feeding rx are driven by 12 bit counters, so their cycles are shorter than this.
+const MAX_PRESSES: u64 = 1 << 16;
+
+pub fn part_one(input: &str) -> Result<u64, SolutionError> {
+    let count = Circuit::parse(input)?.count_pulses(1000);
+    Ok(count.low * count.high)
+}
+
+pub fn part_two(input: &str) -> Result<u64, SolutionError> {
+    let circuit = Circuit::parse(input)?;
+    let rx = circuit.id("rx").ok_or_else(|| SolutionError::new("missing module rx"))?;
+
+    // rx is fed by a single conjunction, which sends a low pulse once all of its inputs sent a high pulse
+    // in the same button press. Every input does so periodically, starting with its first high pulse.
+    let [feeder] = circuit.inputs(rx)[..] else {
+        return Err(SolutionError::new("expected rx to have a single input"));
+    };
+    let cycles = circuit
+        .inputs(feeder)
+        .iter()
+        .map(|&from| {
+            // every input is observed from the initial state of a fresh circuit
+            Circuit::parse(input)?
+                .presses_until(MAX_PRESSES, |it| it.from == from && it.to == feeder && it.pulse == Pulse::High)
+                .ok_or_else(|| {
+                    let (from, to) = (circuit.name(from), circuit.name(feeder));
+                    SolutionError::new(format!("{from} did not send a high pulse to {to} within {MAX_PRESSES} presses"))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(lcm_all(cycles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 32000000);
+    }
+
+    #[test]
+    fn test_part_one2() {
+        let result = part_one(&advent_of_code::template::read_file_part("examples", DAY, 2));
+        assert_eq!(result.unwrap(), 11687500);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let input = "broadcaster -> a\n%a -> inv1, b\n%b -> inv2\n&inv1 -> con\n&inv2 -> con\n&con -> rx\n";
+        // inv1 sends a high pulse every 2nd press, inv2 every 4th press
+        assert_eq!(part_two(input).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_part_two_without_high_pulse() {
+        assert!(part_two("broadcaster -> a\n&a -> rx\n").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use advent_of_code::util::grid2d::Grid2d;
+use advent_of_code::util::position::Position;
+use advent_of_code::util::search::bfs_by;
+use advent_of_code::util::sequence::{degree, interpolate};
+
+advent_of_code::solution!(21);
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let grid = Grid2d::of_lines(input);
+    count_reachable(&grid, 64)
+}
+
+/// Returns the number of steps needed to reach every garden plot within `max_steps` of the start.
+/// If `infinite` is set, the map repeats in every direction. Returns `None` if there is no start.
+fn plot_distances(grid: &Grid2d<char>, max_steps: u64, infinite: bool) -> Option<Vec<u64>> {
+    let (width, height) = grid.size();
+    let start = grid.find_first('S')?;
+    let search = bfs_by([start], |position: &Position| {
+        position.neighbors4()
+            .filter(|it| infinite || it.is_safe(width as i64, height as i64))
+            .filter(|it| grid.get_wrap(it) != '#')
+            // a plot farther away than this cannot be reached in time, which bounds the infinite map
+            .filter(|it| it.distance_manhatten(&start) <= max_steps as i64)
+            .collect::<Vec<_>>()
+    });
+    Some(search.distances().values().copied().collect())
+}
+
+/// Counts the plots which can be reached in exactly `steps` steps.
+/// Any plot reached earlier with the same parity can be reached exactly by stepping back and forth.
+fn count_within(distances: &[u64], steps: u64) -> u64 {
+    distances.iter().filter(|it| **it <= steps && **it % 2 == steps % 2).count() as u64
+}
+
+fn count_reachable(grid: &Grid2d<char>, steps: u64) -> Option<u64> {
+    Some(count_within(&plot_distances(grid, steps, false)?, steps))
+}
+
+/// Counts the plots reachable in exactly `steps` steps on the infinitely repeated map.
+///
+/// Once the reachable area spans several copies of the map, the count grows quadratically with every
+/// additional map size of steps. So the count is sampled at `steps % size + k * size` until the samples
+/// follow a quadratic, which is then evaluated at the requested number of steps.
+/// Returns `None` if there is no start or the quadratic does not yield a whole count.
+fn count_reachable_infinite(grid: &Grid2d<char>, steps: u64) -> Option<u64> {
+    let (size, _) = grid.size();
+    let size = size as u64;
+    let remainder = steps.checked_rem(size)?;
+    let mut samples = 6;
+    loop {
+        let bound = remainder + size * (samples - 1);
+        if steps <= bound {
+            return Some(count_within(&plot_distances(grid, steps, true)?, steps));
+        }
+
+        let distances = plot_distances(grid, bound, true)?;
+        let counts: Vec<i64> = (0..samples)
+            .map(|k| count_within(&distances, remainder + size * k) as i64)
+            .collect();
+        advent_of_code::trace!("sampled", bound = bound, counts = counts);
+        let window = &counts[counts.len() - 5..];
+        if degree(window).is_some_and(|it| it <= 2) {
+            let points: Vec<(i64, i64)> = (samples - 3..samples).map(|k| (k as i64, counts[k as usize])).collect();
+            let target = ((steps - remainder) / size) as i64;
+            return u64::try_from(interpolate(&points, target).to_integer()?).ok();
+        }
+        samples *= 2;
+    }
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    let grid = Grid2d::of_lines(input);
+    count_reachable_infinite(&grid, 26501365)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let grid = Grid2d::of_lines(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(count_reachable(&grid, 6), Some(16));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let grid = Grid2d::of_lines(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(count_reachable_infinite(&grid, 6), Some(16));
+        assert_eq!(count_reachable_infinite(&grid, 10), Some(50));
+        assert_eq!(count_reachable_infinite(&grid, 50), Some(1594));
+        assert_eq!(count_reachable_infinite(&grid, 100), Some(6536));
+        assert_eq!(count_reachable_infinite(&grid, 500), Some(167004));
+        assert_eq!(count_reachable_infinite(&grid, 1000), Some(668697));
+        assert_eq!(count_reachable_infinite(&grid, 5000), Some(16733044));
+    }
+
+    #[test]
+    fn test_missing_start() {
+        assert_eq!(part_one("..\n.#\n"), None);
+        assert_eq!(part_two("..\n.#\n"), None);
+    }
+}
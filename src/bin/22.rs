@@ -0,0 +1,95 @@
+use advent_of_code::template::SolutionError;
+use advent_of_code::util::parse::{map_lines, try_integers};
+use advent_of_code::util::position3::Position3;
+use std::collections::{HashMap, HashSet};
+
+advent_of_code::solution!(22);
+
+struct Brick {
+    start: Position3,
+    end: Position3,
+}
+
+impl Brick {
+    fn parse(line: &str) -> Result<Brick, SolutionError> {
+        let values = try_integers(line).collect::<Result<Vec<i64>, _>>()?;
+        let [x1, y1, z1, x2, y2, z2] = values[..] else {
+            return Err(SolutionError::near(line, "expected a brick like 1,0,1~1,2,1"));
+        };
+        let start = Position3::at(x1.min(x2), y1.min(y2), z1.min(z2));
+        let end = Position3::at(x1.max(x2), y1.max(y2), z1.max(z2));
+        Ok(Brick { start, end })
+    }
+
+    fn footprint(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        (self.start.x..=self.end.x).flat_map(move |x| (self.start.y..=self.end.y).map(move |y| (x, y)))
+    }
+}
+
+/// Lets all bricks fall as far as possible and returns, for each settled brick, the bricks it rests on.
+/// The bricks are returned in the order they settle, so every brick rests only on bricks before it.
+fn settle(mut bricks: Vec<Brick>) -> Vec<HashSet<usize>> {
+    bricks.sort_by_key(|it| it.start.z);
+    // the height and the index of the topmost brick above each column
+    let mut tops: HashMap<(i64, i64), (i64, usize)> = HashMap::new();
+    let mut supported_by = Vec::with_capacity(bricks.len());
+
+    for (index, brick) in bricks.iter().enumerate() {
+        let below: Vec<(i64, usize)> = brick.footprint().filter_map(|it| tops.get(&it).copied()).collect();
+        let floor = below.iter().map(|(height, _)| *height).max().unwrap_or(0);
+        supported_by.push(below.iter()
+            .filter(|(height, _)| *height == floor)
+            .map(|(_, below)| *below)
+            .collect());
+
+        let top = floor + 1 + brick.end.z - brick.start.z;
+        brick.footprint().for_each(|it| {
+            tops.insert(it, (top, index));
+        });
+    }
+    supported_by
+}
+
+pub fn part_one(input: &str) -> Result<usize, SolutionError> {
+    let supported_by = settle(map_lines(input, Brick::parse)?);
+    // a brick is the only support of another brick if that one rests on nothing else
+    let required: HashSet<usize> = supported_by.iter()
+        .filter(|it| it.len() == 1)
+        .flatten()
+        .copied()
+        .collect();
+    Ok(supported_by.len() - required.len())
+}
+
+pub fn part_two(input: &str) -> Result<usize, SolutionError> {
+    let supported_by = settle(map_lines(input, Brick::parse)?);
+    let result = (0..supported_by.len())
+        .map(|removed| {
+            let mut falling = HashSet::from([removed]);
+            for (index, below) in supported_by.iter().enumerate().skip(removed + 1) {
+                if !below.is_empty() && below.is_subset(&falling) {
+                    falling.insert(index);
+                }
+            }
+            falling.len() - 1
+        })
+        .sum();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 5);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 7);
+    }
+}
@@ -0,0 +1,39 @@
+use advent_of_code::util::grid2d::Grid2d;
+use advent_of_code::util::maze::Maze;
+use advent_of_code::util::position::Position;
+
+advent_of_code::solution!(23);
+
+fn longest_hike(input: &str, slippery: bool) -> Option<u64> {
+    let grid = Grid2d::of_lines(input);
+    let (width, height) = grid.size();
+    let start = Position { x: 1, y: 0 };
+    let end = Position { x: width as i64 - 2, y: height as i64 - 1 };
+    let graph = Maze::new(&grid).one_way_slopes(slippery).contract(&[start, end]);
+    graph.longest_simple_path(0, 1)
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    longest_hike(input, true)
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    longest_hike(input, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(94));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(154));
+    }
+}
@@ -0,0 +1,171 @@
+use advent_of_code::template::SolutionError;
+use advent_of_code::util::parse::{map_lines, try_integers};
+use advent_of_code::util::position3::Position3;
+
+advent_of_code::solution!(24);
+
+const TEST_AREA: (i64, i64) = (200000000000000, 400000000000000);
+
+/// The largest absolute velocity per axis considered for the thrown rock.
+const MAX_ROCK_VELOCITY: i64 = 500;
+
+#[derive(Copy, Clone)]
+struct Hailstone {
+    position: Position3,
+    velocity: Position3,
+}
+
+impl Hailstone {
+    fn parse(line: &str) -> Result<Hailstone, SolutionError> {
+        let values = try_integers(line).collect::<Result<Vec<i64>, _>>()?;
+        let [x, y, z, dx, dy, dz] = values[..] else {
+            return Err(SolutionError::near(line, "expected a hailstone like 19, 13, 30 @ -2, 1, -2"));
+        };
+        Ok(Hailstone { position: Position3::at(x, y, z), velocity: Position3::at(dx, dy, dz) })
+    }
+}
+
+fn cross(ax: i128, ay: i128, bx: i128, by: i128) -> i128 {
+    ax * by - ay * bx
+}
+
+/// Returns the time at which the path of `a` crosses the path of `b` in the xy plane,
+/// as numerator and positive denominator, or `None` if the paths are parallel.
+fn crossing_time(a: &Hailstone, b: &Hailstone) -> Option<(i128, i128)> {
+    let (va, vb) = (&a.velocity, &b.velocity);
+    let denominator = cross(va.x as i128, va.y as i128, vb.x as i128, vb.y as i128);
+    if denominator == 0 {
+        return None;
+    }
+    let numerator = cross(
+        (b.position.x - a.position.x) as i128,
+        (b.position.y - a.position.y) as i128,
+        vb.x as i128,
+        vb.y as i128,
+    );
+    Some(if denominator < 0 { (-numerator, -denominator) } else { (numerator, denominator) })
+}
+
+/// Counts the pairs of hailstones whose future paths cross within the square `min..=max` in the xy plane.
+/// All calculations are exact, the crossing is compared as fraction against the scaled bounds.
+fn count_crossings(hailstones: &[Hailstone], (min, max): (i64, i64)) -> usize {
+    let within = |position: i64, velocity: i64, (numerator, denominator): (i128, i128)| {
+        let scaled = position as i128 * denominator + velocity as i128 * numerator;
+        (min as i128 * denominator..=max as i128 * denominator).contains(&scaled)
+    };
+    hailstones.iter().enumerate()
+        .flat_map(|(i, a)| hailstones[i + 1..].iter().map(move |b| (a, b)))
+        .filter(|(a, b)| {
+            let (Some(time_a), Some(time_b)) = (crossing_time(a, b), crossing_time(b, a)) else {
+                return false;
+            };
+            time_a.0 >= 0 && time_b.0 >= 0
+                && within(a.position.x, a.velocity.x, time_a)
+                && within(a.position.y, a.velocity.y, time_a)
+        })
+        .count()
+}
+
+pub fn part_one(input: &str) -> Result<usize, SolutionError> {
+    let hailstones = map_lines(input, Hailstone::parse)?;
+    Ok(count_crossings(&hailstones, TEST_AREA))
+}
+
+/// Returns the time at which `hailstone` reaches the point `(x, y)`, `None` if it never does,
+/// and `Some(None)` if it stands still on that point.
+fn time_at(hailstone: &Hailstone, x: i128, y: i128) -> Option<Option<i128>> {
+    let (position, velocity) = (&hailstone.position, &hailstone.velocity);
+    let (dx, dy) = (x - position.x as i128, y - position.y as i128);
+    let (vx, vy) = (velocity.x as i128, velocity.y as i128);
+    let time = match (vx, vy) {
+        (0, 0) => return (dx == 0 && dy == 0).then_some(None),
+        (0, _) => dy / vy,
+        _ => dx / vx,
+    };
+    (time >= 0 && dx == vx * time && dy == vy * time).then_some(Some(time))
+}
+
+/// Tries to throw the rock with the given velocity in the xy plane and returns its start position if it hits all hailstones.
+///
+/// Relative to the rock, every hailstone has to pass through the start position of the rock.
+/// So the start position is where the first two non-parallel relative paths cross, and the other hailstones are checked against it.
+/// The velocity in z direction then follows from the times two of the hailstones are hit.
+/// The relative hailstones are computed one by one, as most velocities already miss one of the first hailstones.
+fn throw_rock(hailstones: &[Hailstone], vx: i64, vy: i64) -> Option<Position3> {
+    let rock_velocity = Position3::at(vx, vy, 0);
+    let relative = |hailstone: &Hailstone| Hailstone { position: hailstone.position, velocity: hailstone.velocity - rock_velocity };
+
+    let (first, others) = hailstones.split_first()?;
+    let first = relative(first);
+    let (numerator, denominator) = others.iter().find_map(|it| crossing_time(&first, &relative(it)))?;
+    if numerator < 0 || numerator % denominator != 0 {
+        return None;
+    }
+    let time = numerator / denominator;
+    let x = first.position.x as i128 + first.velocity.x as i128 * time;
+    let y = first.position.y as i128 + first.velocity.y as i128 * time;
+
+    let mut hits = Vec::new();
+    for hailstone in hailstones.iter().map(relative) {
+        if let Some(time) = time_at(&hailstone, x, y)? {
+            hits.push((hailstone, time));
+        }
+    }
+
+    let (a, time_a) = *hits.first()?;
+    let (b, time_b) = *hits.iter().find(|(_, time)| *time != time_a)?;
+    let z_at = |hailstone: &Hailstone, time: i128| hailstone.position.z as i128 + hailstone.velocity.z as i128 * time;
+    let distance = z_at(&a, time_a) - z_at(&b, time_b);
+    if distance % (time_a - time_b) != 0 {
+        return None;
+    }
+    let vz = distance / (time_a - time_b);
+    let z = z_at(&a, time_a) - vz * time_a;
+    if !hits.iter().all(|(hailstone, time)| z_at(hailstone, *time) - vz * time == z) {
+        return None;
+    }
+    Some(Position3::at(x as i64, y as i64, z as i64))
+}
+
+pub fn part_two(input: &str) -> Result<i64, SolutionError> {
+    let hailstones = map_lines(input, Hailstone::parse)?;
+    if hailstones.len() < 2 {
+        return Err(SolutionError::new("expected at least two hailstones to aim the rock"));
+    }
+    let rock = (-MAX_ROCK_VELOCITY..=MAX_ROCK_VELOCITY)
+        .flat_map(|vx| (-MAX_ROCK_VELOCITY..=MAX_ROCK_VELOCITY).map(move |vy| (vx, vy)))
+        .find_map(|(vx, vy)| throw_rock(&hailstones, vx, vy))
+        .ok_or_else(|| SolutionError::new("no rock velocity hits all hailstones"))?;
+    Ok(rock.x + rock.y + rock.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        // the paths of the example cross near the origin, far from the test area of the puzzle
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_count_crossings() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let hailstones = map_lines(&input, Hailstone::parse).unwrap();
+        assert_eq!(count_crossings(&hailstones, (7, 27)), 2);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 47);
+    }
+
+    #[test]
+    fn test_part_two_too_few_hailstones() {
+        assert!(part_two("").is_err());
+        assert!(part_two("19, 13, 30 @ -2, 1, -2").is_err());
+    }
+}
@@ -0,0 +1,28 @@
+use advent_of_code::template::SolutionError;
+use advent_of_code::util::graph::parse_edges;
+
+advent_of_code::solution!(25, 1);
+
+/// The number of wires which have to be disconnected.
+const CUT_WIRES: u64 = 3;
+
+pub fn part_one(input: &str) -> Result<usize, SolutionError> {
+    let components = parse_edges(input)?;
+    let (cut, group) = components.min_cut()
+        .ok_or_else(|| SolutionError::new("expected at least two components"))?;
+    if cut != CUT_WIRES {
+        return Err(SolutionError::new(format!("expected to cut {CUT_WIRES} wires, but the minimum cut has {cut}")));
+    }
+    Ok(group.len() * (components.len() - group.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result.unwrap(), 54);
+    }
+}
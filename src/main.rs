@@ -1,4 +1,4 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, download, fuzz_targets, read, scaffold, solve, time};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -8,6 +8,7 @@ use std::process;
 
 mod args {
     use advent_of_code::template::Day;
+    use advent_of_code::util::trace;
     use std::process;
 
     pub enum AppArguments {
@@ -26,6 +27,8 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            viz: bool,
+            trace: Option<trace::Output>,
         },
         All {
             release: bool,
@@ -34,6 +37,11 @@ mod args {
             all: bool,
             day: Option<Day>,
             store: bool,
+            cold: bool,
+        },
+        FuzzTargets {
+            day: Option<Day>,
+            force: bool,
         },
         #[cfg(feature = "today")]
         Today,
@@ -49,11 +57,21 @@ mod args {
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let cold = args.contains("--cold");
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    cold,
+                }
+            }
+            Some("fuzz-targets") => {
+                let force = args.contains("--force");
+
+                AppArguments::FuzzTargets {
+                    day: args.opt_free_from_str()?,
+                    force,
                 }
             }
             Some("download") => AppArguments::Download {
@@ -71,6 +89,14 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                viz: args.contains("--viz"),
+                trace: if args.contains("--trace-log") {
+                    Some(trace::Output::Log)
+                } else if args.contains("--trace") {
+                    Some(trace::Output::Stderr)
+                } else {
+                    None
+                },
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -101,7 +127,8 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::Time { day, all, store, cold } => time::handle(day, all, store, cold),
+            AppArguments::FuzzTargets { day, force } => fuzz_targets::handle(day, force),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold { day, download } => {
@@ -115,7 +142,9 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                viz,
+                trace,
+            } => solve::handle(day, release, dhat, submit, viz, trace),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
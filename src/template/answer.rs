@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt::Display;
+
+/// The result of a solution part.
+///
+/// Integers of any width convert into an [`Answer`] without truncation, so solutions can return
+/// the value they computed instead of casting it to a smaller type. Text answers, including
+/// multi-line output, are supported via [`String`], `&str` and [`Answer::lines`].
+///
+/// ```
+/// # use advent_of_code::template::Answer;
+/// let answer = Answer::from(952408144115_u64);
+/// assert_eq!(answer.to_string(), "952408144115");
+/// assert!(u32::try_from(answer).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl Answer {
+    /// Creates a multi-line text answer by joining the `lines` with newlines.
+    pub fn lines<T: Display>(lines: impl IntoIterator<Item = T>) -> Self {
+        let text = lines.into_iter().map(|it| it.to_string()).collect::<Vec<_>>().join("\n");
+        Answer::Text(text)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(value) => write!(f, "{value}"),
+            Answer::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+macro_rules! impl_integer_conversions {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    match i128::try_from(value) {
+                        Ok(value) => Answer::Number(value),
+                        Err(_) => panic!("answer {value} does not fit into an i128"),
+                    }
+                }
+            }
+
+            impl TryFrom<Answer> for $t {
+                type Error = AnswerConversionError;
+
+                fn try_from(value: Answer) -> Result<Self, Self::Error> {
+                    match value {
+                        Answer::Number(number) => <$t>::try_from(number).map_err(|_| AnswerConversionError::Overflow {
+                            value: number,
+                            target: stringify!($t),
+                        }),
+                        Answer::Text(text) => Err(AnswerConversionError::NotANumber(text)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_conversions!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+/// An error which can be returned when converting an [`Answer`] into an integer.
+#[derive(Debug)]
+pub enum AnswerConversionError {
+    Overflow { value: i128, target: &'static str },
+    NotANumber(String),
+}
+
+impl Error for AnswerConversionError {}
+
+impl Display for AnswerConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswerConversionError::Overflow { value, target } => write!(f, "answer {value} does not fit into {target}"),
+            AnswerConversionError::NotANumber(text) => write!(f, "answer \"{text}\" is not a number"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_integers_without_truncation() {
+        assert_eq!(Answer::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert_eq!(Answer::from(-5_i32), Answer::Number(-5));
+        assert_eq!(u64::try_from(Answer::from(u64::MAX)).unwrap(), u64::MAX);
+        assert!(matches!(
+            u32::try_from(Answer::from(1_u64 << 32)),
+            Err(AnswerConversionError::Overflow { target: "u32", .. })
+        ));
+        assert!(u8::try_from(Answer::from(-1)).is_err());
+        assert!(i32::try_from(Answer::from("abc")).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into an i128")]
+    fn panics_on_u128_overflow() {
+        let _ = Answer::from(u128::MAX);
+    }
+
+    #[test]
+    fn multi_line_text() {
+        assert_eq!(Answer::lines(["#.", ".#"]).to_string(), "#.\n.#");
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+    }
+}
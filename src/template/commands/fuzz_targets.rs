@@ -0,0 +1,118 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+
+use crate::template::{all_days, Day};
+
+const TARGET_TEMPLATE: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template/fuzz_target.txt"));
+
+const MANIFEST_PATH: &str = "fuzz/Cargo.toml";
+
+/// Generates a cargo-fuzz target for the given day, or for every day with a solution, and seeds its corpus.
+/// Existing targets may have been edited by hand, so they are only overwritten with `force`.
+pub fn handle(day: Option<Day>, force: bool) {
+    let days: Vec<Day> = day.map_or_else(
+        || all_days().filter(|day| Path::new(&module_path(*day)).exists()).collect(),
+        |day| vec![day],
+    );
+
+    for day in days {
+        let Ok(module) = fs::read_to_string(module_path(day)) else {
+            eprintln!("Failed to read the solution of day {day}, scaffold it first.");
+            process::exit(1);
+        };
+
+        let target_path = target_path(day);
+        if !force && Path::new(&target_path).exists() {
+            println!("Kept existing fuzz target \"{target_path}\", use `--force` to overwrite it");
+        } else {
+            match write_target(day, &module) {
+                Ok(()) => println!("Created fuzz target \"{target_path}\""),
+                Err(e) => {
+                    eprintln!("Failed to write the fuzz target of day {day}: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+
+        if let Err(e) = register_target(day) {
+            eprintln!("Failed to add the fuzz target of day {day} to \"{MANIFEST_PATH}\": {e}");
+            process::exit(1);
+        }
+
+        match seed_corpus(day) {
+            Ok(seeds) => println!("Seeded the corpus of fuzz target \"{day}\" with {seeds} example(s)"),
+            Err(e) => {
+                eprintln!("Failed to seed the corpus of day {day}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    println!("---");
+    println!("🎄 Type `cargo +nightly fuzz run <day>` to fuzz a solution.");
+}
+
+fn module_path(day: Day) -> String {
+    format!("src/bin/{day}.rs")
+}
+
+fn target_path(day: Day) -> String {
+    format!("fuzz/fuzz_targets/{day}.rs")
+}
+
+/// Writes a target which passes every byte sequence that is valid UTF-8 to the parts the solution defines.
+fn write_target(day: Day, module: &str) -> std::io::Result<()> {
+    let parts: Vec<String> = ["part_one", "part_two"]
+        .iter()
+        .filter(|part| module.contains(&format!("pub fn {part}(")))
+        .map(|part| format!("        let _ = solution::{part}(input);"))
+        .collect();
+
+    fs::create_dir_all("fuzz/fuzz_targets")?;
+    fs::write(
+        target_path(day),
+        TARGET_TEMPLATE
+            .replace("%DAY%", &day.to_string())
+            .replace("%PARTS%", &parts.join("\n")),
+    )
+}
+
+/// Adds the `[[bin]]` section of the target to the manifest of the fuzz crate, unless it is there already.
+fn register_target(day: Day) -> std::io::Result<()> {
+    let manifest = fs::read_to_string(MANIFEST_PATH)?;
+    if manifest.contains(&format!("name = \"{day}\"")) {
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new().append(true).open(MANIFEST_PATH)?;
+    write!(
+        file,
+        "\n[[bin]]\nname = \"{day}\"\npath = \"fuzz_targets/{day}.rs\"\ntest = false\ndoc = false\nbench = false\n"
+    )
+}
+
+/// Copies the examples of the day, e.g. `data/examples/07.txt` and `data/examples/07-2.txt`, into `fuzz/corpus/NN/`.
+/// Returns the number of examples.
+fn seed_corpus(day: Day) -> std::io::Result<usize> {
+    let corpus = format!("fuzz/corpus/{day}");
+    fs::create_dir_all(&corpus)?;
+
+    let mut seeds = 0;
+    for entry in fs::read_dir("data/examples")? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|it| it.to_str()) else {
+            continue;
+        };
+        let is_example = name
+            .strip_suffix(".txt")
+            .is_some_and(|it| it == day.to_string() || it.starts_with(&format!("{day}-")));
+        if is_example && fs::metadata(&path)?.len() > 0 {
+            fs::copy(&path, format!("{corpus}/{name}"))?;
+            seeds += 1;
+        }
+    }
+    Ok(seeds)
+}
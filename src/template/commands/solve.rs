@@ -1,23 +1,37 @@
 use std::process::{Command, Stdio};
 
 use crate::template::Day;
+use crate::util::trace;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>, viz: bool, trace: Option<trace::Output>) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+    let mut features = vec![];
 
     if dhat {
-        cmd_args.extend([
-            "--profile".to_string(),
-            "dhat".to_string(),
-            "--features".to_string(),
-            "dhat-heap".to_string(),
-        ]);
+        cmd_args.extend(["--profile".to_string(), "dhat".to_string()]);
+        features.push("dhat-heap");
     } else if release {
         cmd_args.push("--release".to_string());
     }
 
+    if trace.is_some() {
+        features.push("trace");
+    }
+
+    if !features.is_empty() {
+        cmd_args.extend(["--features".to_string(), features.join(",")]);
+    }
+
     cmd_args.push("--".to_string());
 
+    if trace == Some(trace::Output::Log) {
+        cmd_args.push("--trace-log".to_string());
+    }
+
+    if viz {
+        cmd_args.push("--viz".to_string());
+    }
+
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
@@ -0,0 +1,160 @@
+use std::fmt::Display;
+
+/// A 1-based line and column in the puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Returns the location of `fragment` within `input`.
+    /// `fragment` must be a slice of `input` (e.g. obtained via `lines()` or `split()`), otherwise `None` is returned.
+    ///
+    /// ```
+    /// # use advent_of_code::template::Location;
+    /// let input = "abc\ndef";
+    /// let fragment = &input.lines().nth(1).unwrap()[1..];
+    /// assert_eq!(Location::of(input, fragment), Some(Location { line: 2, column: 2 }));
+    /// ```
+    pub fn of(input: &str, fragment: &str) -> Option<Self> {
+        Self::of_address(input, fragment.as_ptr() as usize, fragment.len())
+    }
+
+    fn of_address(input: &str, address: usize, len: usize) -> Option<Self> {
+        let offset = address.checked_sub(input.as_ptr() as usize)?;
+        if offset + len > input.len() {
+            return None;
+        }
+        let before = input.get(..offset)?;
+        let line_start = before.rfind('\n').map_or(0, |it| it + 1);
+        Some(Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        })
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// An error returned by a solution part, optionally pointing to the part of the input that caused it.
+///
+/// Any [`std::error::Error`] converts into a [`SolutionError`], so `?` can be used in solutions.
+/// Like `Box<dyn Error>`, this type does not implement [`std::error::Error`] itself to make that conversion possible.
+#[derive(Debug)]
+pub struct SolutionError {
+    message: String,
+    location: Option<Location>,
+    /// Address and length of the fragment this error points to, resolved by [`SolutionError::resolve`].
+    fragment: Option<(usize, usize)>,
+}
+
+impl SolutionError {
+    pub fn new(message: impl Display) -> Self {
+        Self { message: message.to_string(), location: None, fragment: None }
+    }
+
+    /// Creates an error pointing to `fragment`, which must be a slice of `input`.
+    pub fn in_input(input: &str, fragment: &str, message: impl Display) -> Self {
+        Self::near(fragment, message).resolve(input)
+    }
+
+    /// Creates an error pointing to `fragment` without knowing the whole input yet.
+    /// The location is resolved once the input is passed to [`SolutionError::at`] or [`SolutionError::resolve`],
+    /// which the runner does before printing the error.
+    pub fn near(fragment: &str, message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            location: None,
+            fragment: Some((fragment.as_ptr() as usize, fragment.len())),
+        }
+    }
+
+    /// Points this error to `fragment`, if it does not point anywhere yet, and resolves it within `input`.
+    /// A fragment given to [`SolutionError::near`] takes precedence over the `fragment` passed here.
+    #[must_use]
+    pub fn at(mut self, input: &str, fragment: &str) -> Self {
+        if self.location.is_none() && self.fragment.is_none() {
+            self.fragment = Some((fragment.as_ptr() as usize, fragment.len()));
+        }
+        self.resolve(input)
+    }
+
+    /// Resolves the fragment this error points to into a location within `input`.
+    /// Resolving again with a larger input, e.g. the whole puzzle input after parsing a part of it,
+    /// replaces the location relative to the smaller one.
+    #[must_use]
+    pub fn resolve(mut self, input: &str) -> Self {
+        if let Some(location) = self.fragment.and_then(|(address, len)| Location::of_address(input, address, len)) {
+            self.location = Some(location);
+        }
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn location(&self) -> Option<Location> {
+        self.location
+    }
+}
+
+impl Display for SolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "{location}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl<E: std::error::Error> From<E> for SolutionError {
+    fn from(value: E) -> Self {
+        SolutionError::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_of_fragment() {
+        let input = "ab\ncdé f\n";
+        let word = input.lines().nth(1).unwrap().split_whitespace().nth(1).unwrap();
+        assert_eq!(Location::of(input, word), Some(Location { line: 2, column: 5 }));
+        assert_eq!(Location::of(input, input), Some(Location { line: 1, column: 1 }));
+        assert_eq!(Location::of(input, &String::from("f")), None);
+    }
+
+    #[test]
+    fn error_display() {
+        let input = "1 2\n3 x";
+        let fragment = &input[6..];
+        let error = SolutionError::from("x".parse::<u32>().unwrap_err()).at(input, fragment);
+        assert_eq!(error.location(), Some(Location { line: 2, column: 3 }));
+        assert_eq!(error.to_string(), "line 2, column 3: invalid digit found in string");
+        assert_eq!(SolutionError::new("no input").to_string(), "no input");
+    }
+
+    #[test]
+    fn resolve_fragment() {
+        let input = "1 2\n3 x";
+        let line = input.lines().nth(1).unwrap();
+        let error = SolutionError::near(&line[2..], "not a number");
+        assert_eq!(error.location(), None);
+        let error = error.at(input, line);
+        assert_eq!(error.location(), Some(Location { line: 2, column: 3 }));
+        assert_eq!(SolutionError::new("unrelated").at(input, line).location(), Some(Location { line: 2, column: 1 }));
+
+        let block = &input[4..];
+        let error = SolutionError::near(&block[2..], "not a number").at(block, block);
+        assert_eq!(error.location(), Some(Location { line: 1, column: 3 }));
+        assert_eq!(error.resolve(input).location(), Some(Location { line: 2, column: 3 }));
+    }
+}
@@ -0,0 +1,228 @@
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+thread_local! {
+    /// Incremented by [`reset_memos`], every [`Memo`] created before that is treated as empty afterwards.
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+fn generation() -> u64 {
+    GENERATION.with(Cell::get)
+}
+
+/// Clears all [`Memo`] caches of the current thread, including ones kept in thread locals.
+/// The runner calls this before every run of a solution part, so benchmark samples do not reuse results.
+pub fn reset_memos() {
+    GENERATION.with(|it| it.set(it.get() + 1));
+}
+
+/// A key which can be looked up in a [`Memo`] in its borrowed form, e.g. `&str` or `(&[u8], &[usize])`.
+/// The key is only converted into its owned form when a value is inserted.
+pub trait MemoKey: Hash {
+    type Owned;
+
+    fn to_owned_key(&self) -> Self::Owned;
+
+    fn matches(&self, owned: &Self::Owned) -> bool;
+}
+
+macro_rules! impl_memo_key_for_owned {
+    ($($t:ty),*) => {
+        $(
+            impl MemoKey for $t {
+                type Owned = $t;
+
+                fn to_owned_key(&self) -> $t {
+                    self.clone()
+                }
+
+                fn matches(&self, owned: &$t) -> bool {
+                    self == owned
+                }
+            }
+        )*
+    };
+}
+
+impl_memo_key_for_owned!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, char, bool, String);
+
+impl MemoKey for str {
+    type Owned = String;
+
+    fn to_owned_key(&self) -> String {
+        self.to_string()
+    }
+
+    fn matches(&self, owned: &String) -> bool {
+        self == owned
+    }
+}
+
+impl<T: Hash + Eq + Clone> MemoKey for [T] {
+    type Owned = Vec<T>;
+
+    fn to_owned_key(&self) -> Vec<T> {
+        self.to_vec()
+    }
+
+    fn matches(&self, owned: &Vec<T>) -> bool {
+        self == owned.as_slice()
+    }
+}
+
+impl<T: MemoKey + ?Sized> MemoKey for &T {
+    type Owned = T::Owned;
+
+    fn to_owned_key(&self) -> T::Owned {
+        (**self).to_owned_key()
+    }
+
+    fn matches(&self, owned: &T::Owned) -> bool {
+        (**self).matches(owned)
+    }
+}
+
+macro_rules! impl_memo_key_for_tuple {
+    ($($name:ident $index:tt),*) => {
+        impl<$($name: MemoKey),*> MemoKey for ($($name,)*) {
+            type Owned = ($($name::Owned,)*);
+
+            fn to_owned_key(&self) -> Self::Owned {
+                ($(self.$index.to_owned_key(),)*)
+            }
+
+            fn matches(&self, owned: &Self::Owned) -> bool {
+                $(self.$index.matches(&owned.$index))&&*
+            }
+        }
+    };
+}
+
+impl_memo_key_for_tuple!(A 0, B 1);
+impl_memo_key_for_tuple!(A 0, B 1, C 2);
+impl_memo_key_for_tuple!(A 0, B 1, C 2, D 3);
+
+/// A cache for the results of a recursive function, looked up by borrowed keys.
+///
+/// Create it in the solution part and pass it down the recursion, so every run starts with an empty cache.
+/// A memo which outlives a run, e.g. in a thread local, is cleared by [`reset_memos`].
+///
+/// ```
+/// # use advent_of_code::template::Memo;
+/// fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+///     if n < 2 {
+///         return n;
+///     }
+///     memo.get_or_compute(&n, |memo| fibonacci(n - 1, memo) + fibonacci(n - 2, memo))
+/// }
+/// assert_eq!(fibonacci(90, &mut Memo::new()), 2880067194370816120);
+/// ```
+pub struct Memo<K: MemoKey + ?Sized, V> {
+    hasher: RandomState,
+    // the entries are grouped by the hash of the borrowed key, so lookups never need an owned key
+    entries: HashMap<u64, Vec<(K::Owned, V)>>,
+    len: usize,
+    generation: u64,
+}
+
+impl<K: MemoKey + ?Sized, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: MemoKey + ?Sized, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            hasher: RandomState::new(),
+            entries: HashMap::new(),
+            len: 0,
+            generation: generation(),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.generation != generation()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        if self.is_stale() {
+            return None;
+        }
+        self.entries.get(&self.hasher.hash_one(key))?
+            .iter()
+            .find(|(owned, _)| key.matches(owned))
+            .map(|(_, value)| value)
+    }
+
+    pub fn insert(&mut self, key: &K, value: V) {
+        if self.is_stale() {
+            self.clear();
+        }
+        let bucket = self.entries.entry(self.hasher.hash_one(key)).or_default();
+        match bucket.iter_mut().find(|(owned, _)| key.matches(owned)) {
+            Some(entry) => entry.1 = value,
+            None => {
+                bucket.push((key.to_owned_key(), value));
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Returns the cached value for `key` or computes and caches it.
+    /// `compute` receives the memo, so it can recurse with it.
+    pub fn get_or_compute(&mut self, key: &K, compute: impl FnOnce(&mut Self) -> V) -> V
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.get(key) {
+            return value.clone();
+        }
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        if self.is_stale() { 0 } else { self.len }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.len = 0;
+        self.generation = generation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_borrowed_keys() {
+        let mut memo: Memo<(&str, &[u8]), u32> = Memo::new();
+        let owned = (String::from("#?.#"), vec![1, 2]);
+        memo.insert(&(owned.0.as_str(), owned.1.as_slice()), 3);
+        assert_eq!(memo.get(&("#?.#", &[1, 2])), Some(&3));
+        assert_eq!(memo.get(&("#?.#", &[1])), None);
+        memo.insert(&("#?.#", &[1, 2]), 4);
+        assert_eq!((memo.get(&("#?.#", &[1, 2])), memo.len()), (Some(&4), 1));
+    }
+
+    #[test]
+    fn reset_clears_existing_memos() {
+        let mut memo: Memo<u64, u64> = Memo::new();
+        memo.insert(&1, 1);
+        reset_memos();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get(&1), None);
+        memo.insert(&2, 2);
+        assert_eq!(memo.len(), 1);
+    }
+}
@@ -2,11 +2,18 @@ use std::{env, fs};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod property;
 pub mod runner;
 
+pub use answer::*;
 pub use day::*;
+pub use error::*;
+pub use memo::*;
 
+mod answer;
 mod day;
+mod error;
+mod memo;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -0,0 +1,375 @@
+//! Differential testing of solution parts against brute-force reference implementations.
+//!
+//! A day declares a reference implementation and a generator of small random inputs in its tests,
+//! and [`Differential::check`] compares both on many generated inputs:
+//!
+//! ```ignore
+//! #[test]
+//! fn part_one_matches_reference() {
+//!     Differential::new(DAY, 1, part_one, reference_one).inputs(generate).check();
+//! }
+//! ```
+//!
+//! The first input on which the part differs from the reference is shrunk to a smaller one which still differs,
+//! written to `data/examples/NN-fail.txt`, and reported with a panic. While that file exists, it is checked first.
+
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Once;
+use std::{env, fs};
+
+use crate::template::runner::PartResult;
+use crate::template::{Answer, Day};
+
+/// A small, seedable random number generator (SplitMix64) for input generators.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the given range, e.g. `rng.between(1..=6)`.
+    pub fn between(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {start}..={end}");
+        match (end - start).checked_add(1) {
+            Some(count) => start + self.next_u64() % count,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64 <= probability
+    }
+
+    /// Returns a random element of a non-empty slice.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.between(0..=items.len() as u64 - 1) as usize]
+    }
+}
+
+/// What a solution part did with an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Answer(Option<Answer>),
+    Error(String),
+    Panic(String),
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs a panic hook which skips the message of panics caught by [`outcome`], as shrinking causes lots of them.
+/// Other panics, also of tests running in parallel, are passed to the previous hook.
+fn install_quiet_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                previous(info);
+            }
+        }));
+    });
+}
+
+fn outcome<R: PartResult>(func: &dyn Fn(&str) -> R, input: &str) -> Outcome {
+    install_quiet_hook();
+    QUIET.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| func(input).into_part_result()));
+    QUIET.set(false);
+    match result {
+        Ok(Ok(answer)) => Outcome::Answer(answer),
+        Ok(Err(error)) => Outcome::Error(error.resolve(input).to_string()),
+        Err(payload) => Outcome::Panic(
+            payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default(),
+        ),
+    }
+}
+
+type Part<'a> = Box<dyn Fn(&str) -> Outcome + 'a>;
+type Generator<'a> = Box<dyn Fn(&mut Rng) -> String + 'a>;
+
+/// Compares a solution part with a reference implementation on generated inputs.
+pub struct Differential<'a> {
+    day: Day,
+    part: u8,
+    solve: Part<'a>,
+    reference: Part<'a>,
+    generate: Option<Generator<'a>>,
+    cases: usize,
+    seed: u64,
+}
+
+/// A generated input on which the part differs from the reference.
+struct Failure {
+    input: String,
+    expected: Outcome,
+    actual: Outcome,
+}
+
+impl<'a> Differential<'a> {
+    /// Number of generated inputs checked unless [`Differential::cases`] is called.
+    pub const DEFAULT_CASES: usize = 1000;
+    /// Upper bound for the successful shrinking steps, in case every candidate is only slightly smaller.
+    const MAX_SHRINK_STEPS: usize = 1000;
+
+    pub fn new<R1: PartResult, R2: PartResult>(
+        day: Day,
+        part: u8,
+        solve: impl Fn(&str) -> R1 + 'a,
+        reference: impl Fn(&str) -> R2 + 'a,
+    ) -> Self {
+        Self {
+            day,
+            part,
+            solve: Box::new(move |input| outcome(&solve, input)),
+            reference: Box::new(move |input| outcome(&reference, input)),
+            generate: None,
+            cases: Self::DEFAULT_CASES,
+            // every part gets its own deterministic sequence of inputs
+            seed: u64::from(day.into_inner()) * 10 + u64::from(part),
+        }
+    }
+
+    /// Sets the generator of random inputs. Inputs on which the reference fails or panics count as invalid and are skipped.
+    #[must_use]
+    pub fn inputs(mut self, generate: impl Fn(&mut Rng) -> String + 'a) -> Self {
+        self.generate = Some(Box::new(generate));
+        self
+    }
+
+    #[must_use]
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Sets the seed of the generator. The `PROPERTY_SEED` environment variable takes precedence, to reproduce a run.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the path of the file the last failing input is written to.
+    pub fn failure_path(&self) -> PathBuf {
+        let cwd = env::current_dir().unwrap();
+        cwd.join("data").join("examples").join(format!("{}-fail.txt", self.day))
+    }
+
+    fn failure(&self, input: &str) -> Option<Failure> {
+        let expected = (self.reference)(input);
+        if !matches!(expected, Outcome::Answer(_)) {
+            return None;
+        }
+        let actual = (self.solve)(input);
+        (actual != expected).then(|| Failure { input: input.to_string(), expected, actual })
+    }
+
+    /// Runs the recorded failing input, if any, and the generated inputs.
+    ///
+    /// # Panics
+    /// Panics with the shrunk input if the part differs from the reference on any of them.
+    pub fn check(self) {
+        let recorded = fs::read_to_string(self.failure_path()).ok();
+        if let Some(failure) = recorded.and_then(|it| self.failure(&it)) {
+            self.report(&failure, "the recorded failing input");
+        }
+
+        let generate = self.generate.as_ref().expect("no input generator, call `inputs` first");
+        let seed = env::var("PROPERTY_SEED").ok().and_then(|it| it.parse().ok()).unwrap_or(self.seed);
+        let mut rng = Rng::new(seed);
+        for case in 0..self.cases {
+            if let Some(failure) = self.failure(&generate(&mut rng)) {
+                let failure = self.shrink(failure);
+                self.report(&failure, &format!("generated input {case} of seed {seed}"));
+            }
+        }
+    }
+
+    /// Replaces the failing input by smaller candidates as long as one of them still fails.
+    fn shrink(&self, mut failure: Failure) -> Failure {
+        for _ in 0..Self::MAX_SHRINK_STEPS {
+            match shrink_candidates(&failure.input).iter().find_map(|it| self.failure(it)) {
+                Some(smaller) => failure = smaller,
+                None => break,
+            }
+        }
+        failure
+    }
+
+    fn report(&self, failure: &Failure, origin: &str) {
+        let path = self.failure_path();
+        if let Err(error) = fs::write(&path, &failure.input) {
+            eprintln!("could not write the failing input to {}: {error}", path.display());
+        }
+        panic!(
+            "day {} part {} differs from the reference on {origin}, written to {}:\n{}\nexpected: {}\nactual:   {}",
+            self.day,
+            self.part,
+            path.display(),
+            failure.input,
+            describe(&failure.expected),
+            describe(&failure.actual),
+        );
+    }
+}
+
+fn describe(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Answer(Some(answer)) => answer.to_string(),
+        Outcome::Answer(None) => "no answer".to_string(),
+        Outcome::Error(error) => format!("error: {error}"),
+        Outcome::Panic(message) => format!("panic: {message}"),
+    }
+}
+
+/// Returns smaller variants of an input, roughly the largest reductions first:
+/// without chunks of lines, without columns of a rectangular grid, without single numbers, and with smaller numbers.
+/// Labels and other words are kept, so the shrunk input still reads like a puzzle input.
+fn shrink_candidates(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let join = |lines: Vec<&str>| lines.iter().map(|it| format!("{it}\n")).collect::<String>();
+    let mut candidates = Vec::new();
+
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        for start in (0..lines.len()).step_by(chunk) {
+            let mut remaining = lines.clone();
+            remaining.drain(start..(start + chunk).min(lines.len()));
+            candidates.push(join(remaining));
+        }
+        chunk /= 2;
+    }
+
+    // a grid has rows of equal length without spaces, a single row must not contain numbers either
+    let width = lines.first().map_or(0, |it| it.len());
+    let is_grid = lines.iter().all(|it| it.len() == width && it.is_ascii() && !it.contains(' '))
+        && (lines.len() > 1 || !input.contains(|it: char| it.is_ascii_digit()));
+    if width > 1 && is_grid {
+        for column in 0..width {
+            candidates.push(lines.iter().map(|it| format!("{}{}\n", &it[..column], &it[column + 1..])).collect());
+        }
+    }
+
+    for (start, end) in spans(input, |it| !it.is_whitespace()) {
+        if !input[start..end].contains(|it: char| it.is_ascii_digit()) {
+            continue;
+        }
+        // also remove the whitespace on one side of the token, so the remaining tokens stay separated as before
+        let (before, after) = (&input[..start], &input[end..]);
+        let line_start = before.is_empty() || before.ends_with('\n');
+        if line_start && (after.is_empty() || after.starts_with('\n')) {
+            // whole lines are removed above already
+            continue;
+        }
+        if line_start {
+            candidates.push(format!("{before}{}", after.trim_start_matches([' ', '\t'])));
+        } else {
+            candidates.push(format!("{}{after}", before.trim_end_matches([' ', '\t'])));
+        }
+    }
+
+    for (start, end) in spans(input, |it| it.is_ascii_digit()) {
+        let Ok(number) = input[start..end].parse::<u128>() else {
+            continue;
+        };
+        for smaller in [0, 1, number / 2, number.saturating_sub(1)] {
+            if smaller < number {
+                candidates.push(format!("{}{smaller}{}", &input[..start], &input[end..]));
+            }
+        }
+    }
+
+    candidates.dedup();
+    candidates
+}
+
+/// Returns the byte ranges of the maximal runs of characters matching `predicate`.
+fn spans(text: &str, predicate: impl Fn(char) -> bool) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (index, char) in text.char_indices() {
+        match (predicate(char), start) {
+            (true, None) => start = Some(index),
+            (false, Some(begin)) => {
+                spans.push((begin, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        spans.push((begin, text.len()));
+    }
+    spans
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums the numbers of an input, but wrongly ignores the ones greater than 20.
+    fn sum_with_bug(input: &str) -> Option<u64> {
+        Some(input.split_whitespace().map(|it| it.parse::<u64>().unwrap()).filter(|it| *it <= 20).sum())
+    }
+
+    fn sum(input: &str) -> Option<u64> {
+        Some(input.split_whitespace().map(|it| it.parse::<u64>().unwrap()).sum())
+    }
+
+    fn numbers(rng: &mut Rng) -> String {
+        (0..rng.between(1..=5))
+            .map(|_| (0..rng.between(1..=6)).map(|_| rng.between(0..=99).to_string()).collect::<Vec<_>>().join(" "))
+            .map(|it| format!("{it}\n"))
+            .collect()
+    }
+
+    #[test]
+    fn passes_matching_parts() {
+        Differential::new(Day::new(1).unwrap(), 1, sum, sum).inputs(numbers).cases(200).check();
+    }
+
+    #[test]
+    fn shrinks_failing_inputs() {
+        let differential = Differential::new(Day::new(1).unwrap(), 1, sum_with_bug, sum);
+        let failure = differential.failure("3 40 7\n12 99\n").unwrap();
+        let shrunk = differential.shrink(failure);
+        assert_eq!(shrunk.input, "21\n");
+        assert_eq!((shrunk.expected, shrunk.actual), (Outcome::Answer(Some(21.into())), Outcome::Answer(Some(0.into()))));
+    }
+
+    #[test]
+    fn generates_numbers_in_range() {
+        let mut rng = Rng::new(7);
+        assert!((0..1000).map(|_| rng.between(3..=5)).all(|it| (3..=5).contains(&it)));
+        assert_eq!(rng.between(4..=4), 4);
+        assert_eq!(*rng.choose(&['x']), 'x');
+    }
+
+    #[test]
+    fn skips_inputs_the_reference_rejects() {
+        let differential = Differential::new(Day::new(1).unwrap(), 1, sum_with_bug, sum);
+        assert!(differential.failure("3 x\n").is_none());
+    }
+}
@@ -7,7 +7,7 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool, is_cold: bool) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
@@ -24,7 +24,7 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output = child_commands::run_solution(day, is_timed, is_release, is_cold).unwrap();
 
             if output.is_empty() {
                 println!("Not solved.");
@@ -77,7 +77,7 @@ pub mod child_commands {
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(day: Day, is_timed: bool, is_release: bool, is_cold: bool) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -94,6 +94,9 @@ pub mod child_commands {
             // mirror `--time` flag to child invocations.
             args.push("--");
             args.push("--time");
+            if is_cold {
+                args.push("--cold");
+            }
         }
 
         // spawn child command with piped stdout/stderr.
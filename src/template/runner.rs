@@ -2,34 +2,96 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::process::{Command, Output};
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, reset_memos, Answer, Day, SolutionError, ANSI_ITALIC, ANSI_RESET};
+use crate::util::{trace, viz};
+
+/// Implemented by the return types accepted for solution parts:
+/// `Option<T>` and `Result<T, E>`, where `T` converts into an [`Answer`] and `E` into a [`SolutionError`].
+pub trait PartResult {
+    fn into_part_result(self) -> Result<Option<Answer>, SolutionError>;
+}
+
+impl<T: Into<Answer>> PartResult for Option<T> {
+    fn into_part_result(self) -> Result<Option<Answer>, SolutionError> {
+        Ok(self.map(Into::into))
+    }
+}
+
+impl<T: Into<Answer>, E: Into<SolutionError>> PartResult for Result<T, E> {
+    fn into_part_result(self) -> Result<Option<Answer>, SolutionError> {
+        self.map(|it| Some(it.into())).map_err(Into::into)
+    }
+}
+
+/// Enables the terminal visualization of [`viz`] for the first run of every part.
+const VIZ_ARG: &str = "--viz";
+/// Writes the events of [`trace`] to the log file of the day instead of stderr, if the `trace` feature is enabled.
+const TRACE_LOG_ARG: &str = "--trace-log";
+/// Passed to the child processes of a `--cold` benchmark, followed by the part they have to run once.
+const COLD_SAMPLE_ARG: &str = "--cold-sample";
+/// Prefixes the duration in nanoseconds that a child process of a `--cold` benchmark prints.
+const COLD_SAMPLE_OUTPUT: &str = "cold sample: ";
+/// Benchmark samples faster than the first run by this factor hint at results kept between samples.
+const SUSPICIOUS_SPEEDUP: u32 = 10;
+/// First runs faster than this are not checked for suspicious speedups, as they are dominated by warmup effects.
+const SPEEDUP_CHECK_THRESHOLD: Duration = Duration::from_millis(1);
+
+pub fn run_part<I: Clone + AsRef<str>, R: PartResult>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
+    if let Some(sample_part) = cold_sample_part() {
+        if sample_part == part {
+            run_cold_sample(func, input);
+        }
+        return;
+    }
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    viz::set_enabled(env::args().any(|x| x == VIZ_ARG));
+    if cfg!(feature = "trace") {
+        let output = if env::args().any(|x| x == TRACE_LOG_ARG) { trace::Output::Log } else { trace::Output::Stderr };
+        trace::start(day, part, output);
+    }
+    let (result, duration, samples, base_time) = run_timed(
+        |input| func(input).into_part_result(),
+        input.clone(),
+        part,
+        |result| print_result(&answer_of(result), &part_str, ""),
+    );
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&answer_of(&result), &part_str, &format_duration(&duration, samples));
+    warn_on_suspicious_speedup(&part_str, &base_time, &duration, samples);
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    match result {
+        Ok(Some(answer)) => {
+            submit_result(answer, day, part);
+        }
+        Ok(None) => {}
+        Err(error) => print_error(&error.resolve(input.as_ref()), input.as_ref(), &part_str),
     }
 }
 
+fn answer_of(result: &Result<Option<Answer>, SolutionError>) -> Option<Answer> {
+    result.as_ref().ok().cloned().flatten()
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///     With `--cold`, every sample runs in a fresh process, so no state survives between samples.
+///
+/// Returns the result, the average duration, the number of samples and the duration of the first run.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    part: u8,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Duration, u128, Duration) {
+    reset_memos();
     let timer = Instant::now();
     let result = {
         let input = input.clone();
@@ -40,16 +102,23 @@ fn run_timed<I: Clone, T>(
         func(input)
     };
     let base_time = timer.elapsed();
+    // benchmark samples run without the visualization and tracing
+    viz::set_enabled(false);
+    trace::stop();
 
     hook(&result);
 
     let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+        if std::env::args().any(|x| x == "--cold") {
+            bench_cold(part, &base_time)
+        } else {
+            bench(func, input, &base_time)
+        }
     } else {
         (base_time, 1)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, base_time)
 }
 
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
@@ -66,6 +135,8 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     for _ in 0..bench_iterations {
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
+        // every sample has to start from empty caches, otherwise it measures cache lookups only
+        reset_memos();
         let timer = Instant::now();
         black_box(func(black_box(cloned)));
         timers.push(timer.elapsed());
@@ -78,6 +149,69 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     )
 }
 
+/// Benches a part by running it once in each of several fresh processes of the current solution binary.
+/// This measures cold performance, without caches or lazily initialized data surviving between samples.
+fn bench_cold(part: u8, base_time: &Duration) -> (Duration, u128) {
+    let mut stdout = stdout();
+
+    print!(" > {ANSI_ITALIC}benching in fresh processes{ANSI_RESET}");
+    let _ = stdout.flush();
+
+    // spawning a process is expensive, so fewer samples are taken than in-process
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(5, 100);
+
+    let executable = env::current_exe().expect("could not determine the solution binary");
+    let timers: Vec<Duration> = (0..bench_iterations)
+        .map(|_| {
+            let output = Command::new(&executable)
+                .args([COLD_SAMPLE_ARG, &part.to_string()])
+                .output()
+                .expect("could not spawn the solution binary");
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .find_map(|line| line.strip_prefix(COLD_SAMPLE_OUTPUT)?.parse().ok())
+                .map(Duration::from_nanos)
+                .expect("the solution binary did not report a sample")
+        })
+        .collect();
+
+    (
+        #[allow(clippy::cast_possible_truncation)]
+        Duration::from_nanos(average_duration(&timers) as u64),
+        bench_iterations,
+    )
+}
+
+/// Returns the part to run once if this process is a sample of a `--cold` benchmark.
+fn cold_sample_part() -> Option<u8> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|it| it == COLD_SAMPLE_ARG)?;
+    args.get(index + 1)?.parse().ok()
+}
+
+fn run_cold_sample<I, R>(func: impl Fn(I) -> R, input: I) {
+    let timer = Instant::now();
+    black_box(func(black_box(input)));
+    println!("{COLD_SAMPLE_OUTPUT}{}", timer.elapsed().as_nanos());
+}
+
+/// Warns if the benchmark samples are much faster than the first run, which usually means that the solution
+/// keeps results in global state, e.g. a static cache, so the samples do not measure the actual work.
+fn warn_on_suspicious_speedup(part: &str, base_time: &Duration, average: &Duration, samples: u128) {
+    // with the visualization, the first run includes rendering and is slower anyway
+    let skip = env::args().any(|x| x == "--cold" || x == VIZ_ARG);
+    if samples == 1 || skip || *base_time < SPEEDUP_CHECK_THRESHOLD {
+        return;
+    }
+    if *average * SUSPICIOUS_SPEEDUP < *base_time {
+        eprintln!(
+            "{part}: warning: the samples took {average:.1?} on average, but the first run took {base_time:.1?}. \
+            State surviving between samples may distort the timing, use `--cold` to bench each sample in a fresh process."
+        );
+    }
+}
+
 fn average_duration(numbers: &[Duration]) -> u128 {
     numbers
         .iter()
@@ -89,12 +223,14 @@ fn average_duration(numbers: &[Duration]) -> u128 {
 fn format_duration(duration: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
+    } else if env::args().any(|x| x == "--cold") {
+        format!(" ({duration:.1?} @ {samples} cold samples)")
     } else {
         format!(" ({duration:.1?} @ {samples} samples)")
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result(result: &Option<Answer>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -129,6 +265,21 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
+/// Print the error of a failed part to stderr, followed by the offending input line if the error has a location.
+fn print_error(error: &SolutionError, input: &str, part: &str) {
+    eprintln!("{part}: {error}");
+
+    let Some(location) = error.location() else {
+        return;
+    };
+    if let Some(line) = input.lines().nth(location.line - 1) {
+        let number = location.line.to_string();
+        let padding = " ".repeat(number.len());
+        eprintln!("{number} | {line}");
+        eprintln!("{padding} | {}^", " ".repeat(location.column - 1));
+    }
+}
+
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
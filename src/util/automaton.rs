@@ -0,0 +1,281 @@
+//! A stepper for simulations on a [`Grid2d`], like cellular automata or rolling rocks.
+//!
+//! A step applies one or more phases to the grid. A phase is either
+//! - synchronous: every cell is computed from its neighborhood in the grid before the phase, or
+//! - in place: the cells are visited one after another in a given order, and each visit may change the grid
+//!   around the cell, so later cells see the changes of earlier ones.
+
+use crate::util::cycle::nth_state;
+use crate::util::grid2d::Grid2d;
+use crate::util::position::Position;
+use std::hash::Hash;
+
+/// Read access to a cell and its neighborhood during a synchronous phase.
+///
+pub struct Cell<'a, T> {
+    pub position: Position,
+    grid: &'a Grid2d<T>,
+}
+
+impl<T: Copy + PartialEq> Cell<'_, T> {
+    pub fn value(&self) -> T {
+        self.grid.get(&self.position)
+    }
+
+    /// Returns the value at the given offset from this cell, or `None` outside the grid.
+    ///
+    pub fn get(&self, offset: Position) -> Option<T> {
+        let position = self.position + offset;
+        position.is_within(self.grid).then(|| self.grid.get(&position))
+    }
+
+    /// Returns the values of the orthogonal neighbors within the grid.
+    ///
+    pub fn neighbors4(&self) -> impl Iterator<Item = T> + '_ {
+        self.position.neighbors4_within(self.grid).map(|it| self.grid.get(&it))
+    }
+
+    /// Returns the values of all eight neighbors within the grid.
+    ///
+    pub fn neighbors8(&self) -> impl Iterator<Item = T> + '_ {
+        self.position.neighbors8_within(self.grid).map(|it| self.grid.get(&it))
+    }
+}
+
+/// Write access to the grid around a visited cell during an in-place phase.
+///
+pub struct CellMut<'a, T> {
+    pub position: Position,
+    grid: &'a mut Grid2d<T>,
+}
+
+impl<T: Copy + PartialEq> CellMut<'_, T> {
+    pub fn value(&self) -> T {
+        self.grid.get(&self.position)
+    }
+
+    pub fn set(&mut self, value: T) {
+        self.grid.set(&self.position, value);
+    }
+
+    /// Returns the value at the given offset from the current position, or `None` outside the grid.
+    ///
+    pub fn get(&self, offset: Position) -> Option<T> {
+        let position = self.position + offset;
+        position.is_within(self.grid).then(|| self.grid.get(&position))
+    }
+
+    /// Swaps the value of the current position with the one at the given offset and follows the value there.
+    ///
+    /// # Panics
+    /// Panics if the target is outside the grid.
+    ///
+    pub fn move_by(&mut self, offset: Position) {
+        let target = self.position + offset;
+        self.grid.swap(&self.position, &target);
+        self.position = target;
+    }
+}
+
+type Rule<'r, T> = Box<dyn FnMut(&Cell<T>) -> T + 'r>;
+type RuleMut<'r, T> = Box<dyn FnMut(&mut CellMut<T>) + 'r>;
+
+enum Phase<'r, T> {
+    Synchronous(Rule<'r, T>),
+    InPlace(Vec<Position>, RuleMut<'r, T>),
+}
+
+fn apply<T: Copy + PartialEq>(phases: &mut [Phase<T>], grid: &mut Grid2d<T>) {
+    for phase in phases {
+        match phase {
+            Phase::Synchronous(rule) => {
+                let previous = grid.clone();
+                for position in previous.positions() {
+                    grid.set(&position, rule(&Cell { position, grid: &previous }));
+                }
+            }
+            Phase::InPlace(order, rule) => {
+                for position in order.iter() {
+                    rule(&mut CellMut { position: *position, grid: &mut *grid });
+                }
+            }
+        }
+    }
+}
+
+/// A grid which is advanced step by step by its phases.
+///
+/// ```
+/// # use advent_of_code::util::automaton::Simulation;
+/// # use advent_of_code::util::grid2d::Grid2d;
+/// // Conway's game of life, the blinker oscillates with a period of two
+/// let mut life = Simulation::new(Grid2d::of_lines(".....\n..#..\n..#..\n..#..\n.....")).synchronous(|cell| {
+///     let alive = cell.neighbors8().filter(|it| *it == '#').count();
+///     if alive == 3 || (alive == 2 && cell.value() == '#') { '#' } else { '.' }
+/// });
+/// life.step();
+/// assert_eq!(life.grid().to_string(), ".....\n.....\n.###.\n.....\n.....\n");
+/// life.skip_to(1001);
+/// assert_eq!(life.grid().to_string(), ".....\n.....\n.###.\n.....\n.....\n");
+/// ```
+///
+pub struct Simulation<'r, T> {
+    grid: Grid2d<T>,
+    phases: Vec<Phase<'r, T>>,
+    steps: usize,
+    frames: Option<Vec<Grid2d<T>>>,
+}
+
+impl<'r, T: Copy + PartialEq> Simulation<'r, T> {
+    /// Creates a simulation without phases, add them with [`Simulation::synchronous`] and [`Simulation::in_place`].
+    ///
+    pub fn new(grid: Grid2d<T>) -> Self {
+        Simulation { grid, phases: Vec::new(), steps: 0, frames: None }
+    }
+
+    /// Adds a phase which computes every cell from the grid before the phase.
+    ///
+    pub fn synchronous(mut self, rule: impl FnMut(&Cell<T>) -> T + 'r) -> Self {
+        self.phases.push(Phase::Synchronous(Box::new(rule)));
+        self
+    }
+
+    /// Adds a phase which visits the cells in `order`, each visit can change the grid around the cell.
+    ///
+    pub fn in_place(mut self, order: Vec<Position>, rule: impl FnMut(&mut CellMut<T>) + 'r) -> Self {
+        self.phases.push(Phase::InPlace(order, Box::new(rule)));
+        self
+    }
+
+    /// Records a copy of the grid before the first and after every simulated step, see [`Simulation::frames`].
+    ///
+    pub fn record(mut self) -> Self {
+        self.frames = Some(vec![self.grid.clone()]);
+        self
+    }
+
+    pub fn grid(&self) -> &Grid2d<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid2d<T> {
+        self.grid
+    }
+
+    /// Returns the number of steps taken so far.
+    ///
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Returns the recorded grids, which is empty unless [`Simulation::record`] was called.
+    ///
+    pub fn frames(&self) -> &[Grid2d<T>] {
+        self.frames.as_deref().unwrap_or_default()
+    }
+
+    /// Applies all phases once and returns whether the grid changed.
+    ///
+    pub fn step(&mut self) -> bool {
+        let previous = self.grid.clone();
+        apply(&mut self.phases, &mut self.grid);
+        self.steps += 1;
+        if let Some(frames) = &mut self.frames {
+            frames.push(self.grid.clone());
+        }
+        self.grid != previous
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        (0..steps).for_each(|_| { self.step(); });
+    }
+
+    /// Steps until the grid does not change anymore and returns the number of steps which changed it.
+    ///
+    pub fn run_until_stable(&mut self) -> usize {
+        let mut changes = 0;
+        while self.step() {
+            changes += 1;
+        }
+        changes
+    }
+}
+
+impl<T: Copy + Eq + Hash> Simulation<'_, T> {
+    /// Advances the simulation until `steps` steps were taken in total.
+    /// Once the grid repeats an earlier state, the remaining steps are skipped using [`nth_state`].
+    /// Frames are only recorded for the steps which are simulated one by one.
+    ///
+    /// # Panics
+    /// Panics if more than `steps` steps were already taken.
+    ///
+    pub fn skip_to(&mut self, steps: usize) {
+        assert!(steps >= self.steps, "cannot go back from step {} to step {steps}", self.steps);
+        let phases = &mut self.phases;
+        let frames = &mut self.frames;
+        self.grid = nth_state(self.grid.clone(), |grid| {
+            let mut next = grid.clone();
+            apply(phases, &mut next);
+            if let Some(frames) = frames {
+                frames.push(next.clone());
+            }
+            next
+        }, steps - self.steps);
+        self.steps = steps;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::position::{EAST, SOUTH};
+
+    #[test]
+    fn synchronous_phase_reads_previous_grid() {
+        // every cell copies its left neighbor, so the pattern moves one cell per step
+        let mut simulation = Simulation::new(Grid2d::of_lines("#...")).synchronous(|cell| {
+            cell.get(Position::at(-1, 0)).unwrap_or('.')
+        });
+        simulation.step();
+        assert_eq!(simulation.grid().to_string(), ".#..\n");
+        assert_eq!(simulation.run_until_stable(), 3);
+        assert_eq!((simulation.grid().to_string(), simulation.steps()), ("....\n".to_string(), 5));
+    }
+
+    #[test]
+    fn in_place_phase_sees_earlier_updates() {
+        let grid = Grid2d::of_lines("o..\n.o.\n..#");
+        let order = grid.positions().collect();
+        // falling sand, visited from the top, so every grain falls in a single step
+        let mut simulation = Simulation::new(grid).in_place(order, |cell| {
+            if cell.value() == 'o' {
+                while cell.get(SOUTH) == Some('.') {
+                    cell.move_by(SOUTH);
+                }
+            }
+        }).record();
+        assert!(simulation.step());
+        assert!(!simulation.step());
+        assert_eq!(simulation.grid().to_string(), "...\n...\noo#\n");
+        assert_eq!(simulation.frames().len(), 3);
+    }
+
+    #[test]
+    fn skips_repeating_states() {
+        let grid = Grid2d::of_lines("#....");
+        let order = grid.positions().collect::<Vec<_>>().into_iter().rev().collect();
+        // the block moves right and wraps around after five steps
+        let mut simulation = Simulation::new(grid).in_place(order, |cell| {
+            if cell.value() == '#' {
+                match cell.get(EAST) {
+                    Some(_) => cell.move_by(EAST),
+                    None => cell.move_by(Position::at(-4, 0)),
+                }
+            }
+        });
+        simulation.skip_to(0);
+        assert_eq!((simulation.grid().to_string(), simulation.steps()), ("#....\n".to_string(), 0));
+        simulation.skip_to(1_000_000_003);
+        assert_eq!((simulation.grid().to_string(), simulation.steps()), ("...#.\n".to_string(), 1_000_000_003));
+    }
+}
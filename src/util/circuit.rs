@@ -0,0 +1,386 @@
+//! A simulator for circuits of modules which send pulses to each other, e.g. flip-flops and conjunctions.
+//!
+//! Pulses are processed in the order they are sent. Every module reacts to a received pulse
+//! by sending at most one pulse to all of its outputs.
+//! The circuit is started by pushing a button which sends a low pulse to the `broadcaster`.
+
+use crate::template::SolutionError;
+use crate::util::parse::map_lines;
+use std::collections::{HashMap, VecDeque};
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pulse {
+    Low,
+    High,
+}
+
+/// A pulse on its way from one module to another, the modules are given by their ids.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signal {
+    pub from: usize,
+    pub to: usize,
+    pub pulse: Pulse,
+}
+
+/// The behavior of a module in a [`Circuit`].
+///
+pub trait Module {
+    /// Handles a `pulse` received from the input with the given index, see [`Circuit::inputs`].
+    /// Returns the pulse to send to all outputs, if any.
+    fn receive(&mut self, input: usize, pulse: Pulse) -> Option<Pulse>;
+
+    /// Called whenever the number of inputs of the module changes while the circuit is built.
+    fn connect(&mut self, _inputs: usize) {}
+
+    /// Appends the internal state of the module, which is used to detect repeating states of the circuit.
+    fn write_state(&self, _state: &mut Vec<bool>) {}
+}
+
+/// Forwards every pulse unchanged.
+///
+pub struct Broadcaster;
+
+impl Module for Broadcaster {
+    fn receive(&mut self, _input: usize, pulse: Pulse) -> Option<Pulse> {
+        Some(pulse)
+    }
+}
+
+/// Ignores high pulses and toggles on a low pulse, sending high when it turns on and low when it turns off.
+///
+#[derive(Default)]
+pub struct FlipFlop {
+    on: bool,
+}
+
+impl Module for FlipFlop {
+    fn receive(&mut self, _input: usize, pulse: Pulse) -> Option<Pulse> {
+        if pulse == Pulse::High {
+            return None;
+        }
+        self.on = !self.on;
+        Some(if self.on { Pulse::High } else { Pulse::Low })
+    }
+
+    fn write_state(&self, state: &mut Vec<bool>) {
+        state.push(self.on);
+    }
+}
+
+/// Remembers the last pulse of each input, initially low,
+/// and sends a low pulse if all of them were high and a high pulse otherwise.
+///
+#[derive(Default)]
+pub struct Conjunction {
+    memory: Vec<Pulse>,
+}
+
+impl Module for Conjunction {
+    fn receive(&mut self, input: usize, pulse: Pulse) -> Option<Pulse> {
+        self.memory[input] = pulse;
+        let all_high = self.memory.iter().all(|it| *it == Pulse::High);
+        Some(if all_high { Pulse::Low } else { Pulse::High })
+    }
+
+    fn connect(&mut self, inputs: usize) {
+        self.memory.resize(inputs, Pulse::Low);
+    }
+
+    fn write_state(&self, state: &mut Vec<bool>) {
+        state.extend(self.memory.iter().map(|it| *it == Pulse::High));
+    }
+}
+
+/// A module which never sends pulses, used for modules which are only named as outputs, like `rx`.
+///
+pub struct Sink;
+
+impl Module for Sink {
+    fn receive(&mut self, _input: usize, _pulse: Pulse) -> Option<Pulse> {
+        None
+    }
+}
+
+/// The number of low and high pulses sent.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PulseCount {
+    pub low: u64,
+    pub high: u64,
+}
+
+impl Add for PulseCount {
+    type Output = PulseCount;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        PulseCount { low: self.low + rhs.low, high: self.high + rhs.high }
+    }
+}
+
+impl Sub for PulseCount {
+    type Output = PulseCount;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        PulseCount { low: self.low - rhs.low, high: self.high - rhs.high }
+    }
+}
+
+impl Mul<u64> for PulseCount {
+    type Output = PulseCount;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        PulseCount { low: self.low * rhs, high: self.high * rhs }
+    }
+}
+
+/// Modules connected by their names, with a `button` module wired to the `broadcaster`.
+///
+/// ```
+/// # use advent_of_code::util::circuit::Circuit;
+/// let input = "broadcaster -> a, b, c\n%a -> b\n%b -> c\n%c -> inv\n&inv -> a";
+/// let count = Circuit::parse(input).unwrap().push_button();
+/// assert_eq!((count.low, count.high), (8, 4));
+/// let count = Circuit::parse(input).unwrap().count_pulses(1000);
+/// assert_eq!(count.low * count.high, 32000000);
+/// ```
+///
+pub struct Circuit<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, usize>,
+    modules: Vec<Box<dyn Module>>,
+    outputs: Vec<Vec<usize>>,
+    inputs: Vec<Vec<usize>>,
+    button: usize,
+    queue: VecDeque<Signal>,
+}
+
+impl Default for Circuit<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Circuit<'a> {
+    pub const BUTTON: &'static str = "button";
+    pub const BROADCASTER: &'static str = "broadcaster";
+
+    /// Creates a circuit which only contains the button, wired to the broadcaster.
+    ///
+    pub fn new() -> Self {
+        let mut circuit = Circuit {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            modules: Vec::new(),
+            outputs: Vec::new(),
+            inputs: Vec::new(),
+            button: 0,
+            queue: VecDeque::new(),
+        };
+        circuit.add(Self::BUTTON, Sink, &[Self::BROADCASTER]);
+        circuit
+    }
+
+    /// Parses one module per line, e.g. `%a -> inv, con`.
+    /// The prefix `%` declares a [`FlipFlop`], `&` a [`Conjunction`], and `broadcaster` is a [`Broadcaster`].
+    ///
+    pub fn parse(input: &'a str) -> Result<Circuit<'a>, SolutionError> {
+        let mut circuit = Circuit::new();
+        let mut has_broadcaster = false;
+        map_lines(input, |line| {
+            let (module, outputs) = line.split_once("->")
+                .ok_or_else(|| SolutionError::near(line, "expected \"module -> outputs\""))?;
+            let module = module.trim();
+            let outputs: Vec<&str> = outputs.split(',').map(str::trim).filter(|it| !it.is_empty()).collect();
+            match module.as_bytes().first() {
+                Some(b'%') => circuit.add(&module[1..], FlipFlop::default(), &outputs),
+                Some(b'&') => circuit.add(&module[1..], Conjunction::default(), &outputs),
+                _ if module == Self::BROADCASTER => {
+                    has_broadcaster = true;
+                    circuit.add(module, Broadcaster, &outputs)
+                }
+                _ => return Err(SolutionError::near(module, "expected %, & or broadcaster")),
+            };
+            Ok(())
+        })?;
+        if !has_broadcaster {
+            return Err(SolutionError::new("missing broadcaster"));
+        }
+        Ok(circuit)
+    }
+
+    fn intern(&mut self, name: &'a str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len();
+        self.ids.insert(name, id);
+        self.names.push(name);
+        self.modules.push(Box::new(Sink));
+        self.outputs.push(Vec::new());
+        self.inputs.push(Vec::new());
+        id
+    }
+
+    /// Adds the module `name` sending to `outputs`. A module of the same name is replaced, but keeps its connections.
+    /// Outputs which are never added themselves act as [`Sink`]s.
+    ///
+    pub fn add(&mut self, name: &'a str, mut module: impl Module + 'static, outputs: &[&'a str]) -> usize {
+        let id = self.intern(name);
+        module.connect(self.inputs[id].len());
+        self.modules[id] = Box::new(module);
+        for output in outputs {
+            let target = self.intern(output);
+            self.outputs[id].push(target);
+            self.inputs[target].push(id);
+            let inputs = self.inputs[target].len();
+            self.modules[target].connect(inputs);
+        }
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    /// Returns the ids of the modules sending to `id`, the index in this list is the input index passed to [`Module::receive`].
+    ///
+    pub fn inputs(&self, id: usize) -> &[usize] {
+        &self.inputs[id]
+    }
+
+    pub fn outputs(&self, id: usize) -> &[usize] {
+        &self.outputs[id]
+    }
+
+    /// Pushes the button and processes pulses until the circuit settles.
+    ///
+    pub fn push_button(&mut self) -> PulseCount {
+        self.push_button_with(|_| {})
+    }
+
+    /// Pushes the button and calls `observe` for every pulse in the order they are processed,
+    /// starting with the low pulse from the button.
+    ///
+    pub fn push_button_with(&mut self, mut observe: impl FnMut(&Signal)) -> PulseCount {
+        let mut count = PulseCount::default();
+        self.send(self.button, Pulse::Low);
+        while let Some(signal) = self.queue.pop_front() {
+            observe(&signal);
+            match signal.pulse {
+                Pulse::Low => count.low += 1,
+                Pulse::High => count.high += 1,
+            }
+            let input = self.inputs[signal.to].iter().position(|it| *it == signal.from).unwrap();
+            if let Some(pulse) = self.modules[signal.to].receive(input, signal.pulse) {
+                self.send(signal.to, pulse);
+            }
+        }
+        count
+    }
+
+    fn send(&mut self, from: usize, pulse: Pulse) {
+        self.queue.extend(self.outputs[from].iter().map(|to| Signal { from, to: *to, pulse }));
+    }
+
+    /// Pushes the button until the module `to` receives `pulse` and returns the number of pushes,
+    /// or `None` if it did not happen within `limit` pushes.
+    ///
+    pub fn presses_until_received(&mut self, to: usize, pulse: Pulse, limit: u64) -> Option<u64> {
+        self.presses_until(limit, |it| it.to == to && it.pulse == pulse)
+    }
+
+    /// Pushes the button until a pulse matches `condition` and returns the number of pushes,
+    /// or `None` if none did within `limit` pushes.
+    ///
+    pub fn presses_until(&mut self, limit: u64, mut condition: impl FnMut(&Signal) -> bool) -> Option<u64> {
+        for presses in 1..=limit {
+            let mut matched = false;
+            self.push_button_with(|it| matched |= condition(it));
+            if matched {
+                return Some(presses);
+            }
+        }
+        None
+    }
+
+    /// Returns the combined internal state of all modules, see [`Module::write_state`].
+    ///
+    pub fn snapshot(&self) -> Vec<bool> {
+        let mut state = Vec::new();
+        self.modules.iter().for_each(|it| it.write_state(&mut state));
+        state
+    }
+
+    /// Counts the pulses sent by pushing the button `presses` times.
+    /// Once the circuit returns to an earlier state, the remaining presses are extrapolated,
+    /// so the circuit is then left in the state of the last simulated press.
+    ///
+    pub fn count_pulses(&mut self, presses: u64) -> PulseCount {
+        let mut seen = HashMap::from([(self.snapshot(), 0)]);
+        // the counts after each number of presses
+        let mut totals = vec![PulseCount::default()];
+        for press in 1..=presses as usize {
+            let total = totals[press - 1] + self.push_button();
+            totals.push(total);
+            let snapshot = self.snapshot();
+            if let Some(&start) = seen.get(&snapshot) {
+                let length = press - start;
+                let remaining = presses as usize - press;
+                let rest = totals[start + remaining % length] - totals[start];
+                return total + (total - totals[start]) * (remaining / length) as u64 + rest;
+            }
+            seen.insert(snapshot, press);
+        }
+        totals[presses as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output";
+
+    #[test]
+    fn counts_pulses() {
+        let mut circuit = Circuit::parse(EXAMPLE).unwrap();
+        assert_eq!(circuit.push_button(), PulseCount { low: 4, high: 4 });
+        let mut expected = Circuit::parse(EXAMPLE).unwrap();
+        let total = (0..1000).fold(PulseCount::default(), |acc, _| acc + expected.push_button());
+        assert_eq!(Circuit::parse(EXAMPLE).unwrap().count_pulses(1000), total);
+        assert_eq!(total.low * total.high, 11687500);
+    }
+
+    #[test]
+    fn observes_received_pulses() {
+        let mut circuit = Circuit::parse(EXAMPLE).unwrap();
+        let output = circuit.id("output").unwrap();
+        let mut signals = Vec::new();
+        circuit.push_button_with(|it| signals.push(*it));
+        let order: Vec<_> = signals.iter().map(|it| (circuit.name(it.from), circuit.name(it.to), it.pulse)).collect();
+        assert_eq!(order[..3], [("button", "broadcaster", Pulse::Low), ("broadcaster", "a", Pulse::Low), ("a", "inv", Pulse::High)]);
+        let mut circuit = Circuit::parse(EXAMPLE).unwrap();
+        assert_eq!(circuit.presses_until_received(output, Pulse::Low, 10), Some(1));
+        assert_eq!(circuit.presses_until_received(circuit.id("a").unwrap(), Pulse::High, 10), None);
+        let (inv, b) = (circuit.id("inv").unwrap(), circuit.id("b").unwrap());
+        let mut circuit = Circuit::parse(EXAMPLE).unwrap();
+        assert_eq!(circuit.presses_until(10, |it| it.from == inv && it.to == b && it.pulse == Pulse::High), Some(2));
+    }
+
+    #[test]
+    fn detects_repeated_states() {
+        let mut circuit = Circuit::parse(EXAMPLE).unwrap();
+        let start = circuit.snapshot();
+        circuit.push_button();
+        assert_ne!(circuit.snapshot(), start);
+        (0..3).for_each(|_| { circuit.push_button(); });
+        assert_eq!(circuit.snapshot(), start);
+        assert!(Circuit::parse("%a -> b").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Cycle detection for simulations which eventually repeat a state.
+//! The states are compared by equality, never only by a hash, so colliding states cannot cause wrong results.
+//!
+//! All functions take a `start` state and a `step` function producing the next state.
+//! A sequence `x0, x1, ...` which repeats has a `prefix` of states before the cycle starts
+//! and a cycle of `length` states, i.e. `x(n) == x(n + length)` for every `n >= prefix`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The states of a sequence up to the first repetition, see [`find_cycle`].
+///
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    pub prefix: usize,
+    pub length: usize,
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// Returns the state after `n` steps, extrapolating beyond the recorded states.
+    ///
+    pub fn nth_state(&self, n: usize) -> &S {
+        if n < self.states.len() {
+            &self.states[n]
+        } else {
+            &self.states[self.prefix + (n - self.prefix) % self.length]
+        }
+    }
+
+    /// Returns all distinct states in order, the prefix followed by one iteration of the cycle.
+    ///
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
+
+/// Records the states until one repeats, remembering every state in a [`HashMap`].
+/// Each state is stepped only once, which makes this the fastest option for expensive steps.
+///
+/// ```
+/// # use advent_of_code::util::cycle::find_cycle;
+/// let cycle = find_cycle(3, |it| (it * it + 1) % 10);
+/// // 3, 0, 1, 2, 5, 6, 7, 0, ...
+/// assert_eq!((cycle.prefix, cycle.length), (1, 6));
+/// assert_eq!(*cycle.nth_state(1_000_000_000), 5);
+/// ```
+///
+pub fn find_cycle<S: Hash + Eq + Clone>(start: S, step: impl FnMut(&S) -> S) -> Cycle<S> {
+    match walk(start, step, usize::MAX) {
+        Walk::Cycle(cycle) => cycle,
+        Walk::Reached(_) => unreachable!("the walk is not limited"),
+    }
+}
+
+/// Returns the state after `n` steps, stepping at most until the first repetition.
+///
+pub fn nth_state<S: Hash + Eq + Clone>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    match walk(start, step, n) {
+        Walk::Cycle(cycle) => cycle.nth_state(n).clone(),
+        Walk::Reached(state) => state,
+    }
+}
+
+enum Walk<S> {
+    Cycle(Cycle<S>),
+    Reached(S),
+}
+
+fn walk<S: Hash + Eq + Clone>(start: S, mut step: impl FnMut(&S) -> S, limit: usize) -> Walk<S> {
+    let mut seen = HashMap::from([(start.clone(), 0)]);
+    let mut states = vec![start];
+    loop {
+        // the last state is the one after `states.len() - 1` steps
+        if states.len() - 1 == limit {
+            return Walk::Reached(states.pop().unwrap());
+        }
+        let next = step(states.last().unwrap());
+        if let Some(&prefix) = seen.get(&next) {
+            let length = states.len() - prefix;
+            return Walk::Cycle(Cycle { prefix, length, states });
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+/// Floyd's tortoise and hare algorithm, returns `(prefix, length)` using constant memory.
+///
+/// See <https://en.wikipedia.org/wiki/Cycle_detection#Floyd's_tortoise_and_hare>
+///
+pub fn floyd<S: Eq + Clone>(start: S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    (prefix, length)
+}
+
+/// Brent's algorithm, returns `(prefix, length)` using constant memory and fewer steps than [`floyd`].
+///
+/// See <https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm>
+///
+pub fn brent<S: Eq + Clone>(start: S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut prefix = 0;
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    (prefix, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(value: &u64) -> u64 {
+        (value * value + 1) % 255
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        for start in 0..255 {
+            let cycle = find_cycle(start, step);
+            assert_eq!(floyd(start, step), (cycle.prefix, cycle.length));
+            assert_eq!(brent(start, step), (cycle.prefix, cycle.length));
+        }
+    }
+
+    #[test]
+    fn extrapolates_states() {
+        let mut expected = 7;
+        for n in 0..500 {
+            assert_eq!(nth_state(7, step, n), expected);
+            assert_eq!(*find_cycle(7, step).nth_state(n), expected);
+            expected = step(&expected);
+        }
+    }
+
+    #[test]
+    fn stops_at_goal_before_cycle() {
+        let mut steps = 0;
+        let state = nth_state(0_u64, |it| { steps += 1; it + 1 }, 10);
+        assert_eq!((state, steps), (10, 10));
+    }
+
+    #[test]
+    fn zero_steps_return_start() {
+        let mut steps = 0;
+        let state = nth_state(0_u64, |it| { steps += 1; it + 1 }, 0);
+        assert_eq!((state, steps), (0, 0));
+
+        let mut steps = 0;
+        let state = nth_state(7, |it| { steps += 1; step(it) }, 0);
+        assert_eq!((state, steps), (7, 0));
+    }
+}
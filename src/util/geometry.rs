@@ -0,0 +1,152 @@
+//! Geometry on closed paths of lattice points.
+//! A path is given by its vertices in order, the last vertex is implicitly connected to the first one.
+//! Repeating the first vertex at the end is allowed, as is listing every point along straight edges.
+
+use crate::util::math::gcd;
+use crate::util::position::Position;
+
+fn edges(path: &[Position]) -> impl Iterator<Item = (&Position, &Position)> {
+    path.iter().zip(path.iter().cycle().skip(1))
+}
+
+/// Calculates twice the signed area of the polygon using the shoelace formula.
+/// The result is positive if the vertices are ordered counterclockwise in a coordinate system with `y` pointing up,
+/// which is clockwise when `y` points down like in a [`crate::util::grid2d::Grid2d`].
+///
+/// See <https://en.wikipedia.org/wiki/Shoelace_formula>
+///
+pub fn double_signed_area(path: &[Position]) -> i64 {
+    edges(path).map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+}
+
+/// Calculates the signed area of the polygon, see [`double_signed_area`] for the sign.
+///
+pub fn signed_area(path: &[Position]) -> f64 {
+    double_signed_area(path) as f64 / 2.0
+}
+
+/// Calculates the euclidean length of the polygon boundary.
+///
+pub fn perimeter(path: &[Position]) -> f64 {
+    edges(path)
+        .map(|(a, b)| (((b.x - a.x).pow(2) + (b.y - a.y).pow(2)) as f64).sqrt())
+        .sum()
+}
+
+/// Counts the lattice points on the polygon boundary, including the vertices.
+/// Assumes a simple polygon, points on overlapping edges, e.g. of a path doubling back, are counted twice.
+///
+pub fn boundary_points(path: &[Position]) -> u64 {
+    edges(path)
+        .map(|(a, b)| gcd(a.x.abs_diff(b.x), a.y.abs_diff(b.y)))
+        .sum()
+}
+
+/// Counts the lattice points strictly inside the polygon using Pick's theorem (A = i + b/2 - 1).
+/// Degenerate polygons without area, e.g. a path back and forth along a line, have no interior points.
+///
+/// See <https://en.wikipedia.org/wiki/Pick%27s_theorem>
+///
+pub fn interior_points(path: &[Position]) -> u64 {
+    let double_area = double_signed_area(path).unsigned_abs();
+    (double_area + 2).saturating_sub(boundary_points(path)) / 2
+}
+
+/// Counts the lattice points inside the polygon or on its boundary.
+/// Like [`boundary_points`], this assumes a simple polygon.
+///
+pub fn lattice_points(path: &[Position]) -> u64 {
+    interior_points(path) + boundary_points(path)
+}
+
+/// Returns whether `point` lies on one of the edges of the polygon.
+///
+pub fn on_boundary(path: &[Position], point: &Position) -> bool {
+    edges(path).any(|(a, b)| {
+        let cross = (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x);
+        cross == 0
+            && point.x >= a.x.min(b.x) && point.x <= a.x.max(b.x)
+            && point.y >= a.y.min(b.y) && point.y <= a.y.max(b.y)
+    })
+}
+
+/// Returns whether `point` lies strictly inside the polygon.
+/// Points on the boundary are not contained, use [`on_boundary`] to check for those.
+///
+pub fn contains(path: &[Position], point: &Position) -> bool {
+    if on_boundary(path, point) {
+        return false;
+    }
+    // cast a ray towards positive x and count the crossed edges
+    edges(path)
+        .filter(|(a, b)| (a.y > point.y) != (b.y > point.y))
+        .filter(|(a, b)| {
+            // point.x < a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y), multiplied by (b.y - a.y)
+            let lhs = (point.x - a.x) * (b.y - a.y);
+            let rhs = (point.y - a.y) * (b.x - a.x);
+            if b.y > a.y { lhs < rhs } else { lhs > rhs }
+        })
+        .count() % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<Position> {
+        vec![Position::at(0, 0), Position::at(4, 0), Position::at(4, 4), Position::at(0, 4)]
+    }
+
+    #[test]
+    fn square_measures() {
+        let square = square();
+        assert_eq!(double_signed_area(&square), 32);
+        assert_eq!(signed_area(&square.iter().rev().copied().collect::<Vec<_>>()), -16.0);
+        assert_eq!(perimeter(&square), 16.0);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(lattice_points(&square), 25);
+    }
+
+    #[test]
+    fn degenerate_polygon_has_no_interior() {
+        let line = [Position::at(0, 0), Position::at(2, 0)];
+        assert_eq!(double_signed_area(&line), 0);
+        assert_eq!(interior_points(&line), 0);
+    }
+
+    #[test]
+    fn closing_vertex_is_optional() {
+        let mut closed = square();
+        closed.push(Position::at(0, 0));
+        assert_eq!(double_signed_area(&closed), 32);
+        assert_eq!(boundary_points(&closed), 16);
+    }
+
+    #[test]
+    fn triangle_with_diagonal() {
+        let triangle = [Position::at(0, 0), Position::at(4, 0), Position::at(0, 4)];
+        assert_eq!(boundary_points(&triangle), 12);
+        assert_eq!(interior_points(&triangle), 3);
+        assert!(contains(&triangle, &Position::at(1, 1)));
+        assert!(on_boundary(&triangle, &Position::at(2, 2)));
+        assert!(!contains(&triangle, &Position::at(2, 2)));
+        assert!(!contains(&triangle, &Position::at(3, 3)));
+    }
+
+    #[test]
+    fn contains_concave() {
+        // U-shape opening to the top
+        let shape = [
+            Position::at(0, 0), Position::at(2, 0), Position::at(2, 4), Position::at(4, 4),
+            Position::at(4, 0), Position::at(6, 0), Position::at(6, 6), Position::at(0, 6),
+        ];
+        assert!(contains(&shape, &Position::at(1, 1)));
+        assert!(!contains(&shape, &Position::at(3, 2)));
+        assert!(contains(&shape, &Position::at(3, 5)));
+        assert!(contains(&shape, &Position::at(5, 1)));
+        assert_eq!((0..7).flat_map(|y| (0..7).map(move |x| Position::at(x, y)))
+                       .filter(|it| contains(&shape, it))
+                       .count() as u64, interior_points(&shape));
+    }
+}
@@ -0,0 +1,455 @@
+//! A weighted directed graph whose nodes are identified by labels, e.g. the names used in the puzzle input.
+//! Labels are interned into dense indices, so algorithms work on plain `usize` node ids and `Vec`s.
+
+use crate::template::SolutionError;
+use crate::util::parse::map_lines;
+use crate::util::search::{bfs_by, dijkstra_all_by, Search};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct Graph<L> {
+    labels: Vec<L>,
+    ids: HashMap<L, usize>,
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl<L: Clone + Eq + Hash> Default for Graph<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Clone + Eq + Hash> Graph<L> {
+    pub fn new() -> Self {
+        Self { labels: Vec::new(), ids: HashMap::new(), edges: Vec::new() }
+    }
+
+    /// Returns the id of the node with `label`, adding the node if it does not exist yet.
+    ///
+    pub fn intern(&mut self, label: L) -> usize {
+        if let Some(id) = self.ids.get(&label) {
+            return *id;
+        }
+        let id = self.labels.len();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    pub fn id(&self, label: &L) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: usize) -> &L {
+        &self.labels[id]
+    }
+
+    /// Returns the number of nodes.
+    ///
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> std::ops::Range<usize> {
+        0..self.len()
+    }
+
+    /// Adds a directed edge, adding missing nodes. Parallel edges are kept.
+    ///
+    pub fn add_edge(&mut self, from: L, to: L, weight: u64) {
+        let (from, to) = (self.intern(from), self.intern(to));
+        self.edges[from].push((to, weight));
+    }
+
+    /// Adds an edge in both directions.
+    ///
+    pub fn add_undirected_edge(&mut self, a: L, b: L, weight: u64) {
+        let (a, b) = (self.intern(a), self.intern(b));
+        self.edges[a].push((b, weight));
+        self.edges[b].push((a, weight));
+    }
+
+    /// Returns the outgoing edges of `id` as `(target, weight)` in the order they were added.
+    ///
+    pub fn successors(&self, id: usize) -> &[(usize, u64)] {
+        &self.edges[id]
+    }
+
+    pub fn neighbors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.edges[id].iter().map(|it| it.0)
+    }
+
+    fn reversed_edges(&self) -> Vec<Vec<usize>> {
+        let mut reversed = vec![Vec::new(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, _) in edges {
+                reversed[*to].push(from);
+            }
+        }
+        reversed
+    }
+
+    /// Counts the edges leading to `id` for every node.
+    ///
+    fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.len()];
+        self.edges.iter().flatten().for_each(|(to, _)| degrees[*to] += 1);
+        degrees
+    }
+
+    /// Breadth-first search from `start`, the distances count edges and ignore the weights.
+    ///
+    pub fn bfs(&self, start: usize) -> Search<usize> {
+        bfs_by([start], |it| self.neighbors(*it))
+    }
+
+    /// Dijkstra's algorithm from `start` using the edge weights.
+    ///
+    pub fn dijkstra(&self, start: usize) -> Search<usize> {
+        dijkstra_all_by([start], |it| self.edges[*it].iter().copied())
+    }
+
+    /// Orders the nodes so that every edge points from an earlier to a later node, using Kahn's algorithm.
+    /// Returns `None` if the graph contains a cycle.
+    ///
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        let mut degrees = self.in_degrees();
+        let mut queue: VecDeque<usize> = self.nodes().filter(|it| degrees[*it] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(current) = queue.pop_front() {
+            order.push(current);
+            for next in self.neighbors(current) {
+                degrees[next] -= 1;
+                if degrees[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Finds the strongly connected components with Kosaraju's algorithm.
+    /// The components are returned in topological order of the condensed graph.
+    ///
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        // first pass: order the nodes by the time their depth-first search finishes
+        let mut visited = vec![false; self.len()];
+        let mut finished = Vec::with_capacity(self.len());
+        for root in self.nodes() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack = vec![(root, 0)];
+            while let Some((node, index)) = stack.pop() {
+                if let Some((next, _)) = self.edges[node].get(index) {
+                    stack.push((node, index + 1));
+                    if !visited[*next] {
+                        visited[*next] = true;
+                        stack.push((*next, 0));
+                    }
+                } else {
+                    finished.push(node);
+                }
+            }
+        }
+
+        // second pass: collect the nodes reachable in the reversed graph, latest finished first
+        let reversed = self.reversed_edges();
+        let mut assigned = vec![false; self.len()];
+        let mut components = Vec::new();
+        for root in finished.into_iter().rev() {
+            if assigned[root] {
+                continue;
+            }
+            assigned[root] = true;
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                for next in &reversed[node] {
+                    if !assigned[*next] {
+                        assigned[*next] = true;
+                        component.push(*next);
+                        stack.push(*next);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Finds the heaviest path from `start` to `end` in a directed acyclic graph.
+    /// Returns `None` if `end` is not reachable or the graph contains a cycle,
+    /// in which case finding the longest simple path is NP-hard.
+    ///
+    pub fn longest_path(&self, start: usize, end: usize) -> Option<u64> {
+        let mut lengths: Vec<Option<u64>> = vec![None; self.len()];
+        lengths[start] = Some(0);
+        for node in self.topological_sort()? {
+            let Some(length) = lengths[node] else { continue };
+            for (next, weight) in &self.edges[node] {
+                let candidate = length + weight;
+                if lengths[*next].is_none_or(|it| it < candidate) {
+                    lengths[*next] = Some(candidate);
+                }
+            }
+        }
+        lengths[end]
+    }
+
+    /// Finds the longest path from `start` to `end` which visits no node twice, in a graph which may contain cycles.
+    /// The search is exhaustive and tracks the visited nodes in a bitmask,
+    /// so it is meant for small graphs, e.g. the junctions of a maze, see [`crate::util::maze`].
+    ///
+    /// # Panics
+    /// Panics if the graph has more than 128 nodes.
+    ///
+    pub fn longest_simple_path(&self, start: usize, end: usize) -> Option<u64> {
+        assert!(self.len() <= 128, "the visited bitmask supports at most 128 nodes, got {}", self.len());
+        // once the only node leading to the end is reached, any other way would block the end
+        let reversed = self.reversed_edges();
+        let last = match reversed[end][..] {
+            [last] if last != start => Some(last),
+            _ => None,
+        };
+        self.longest_simple_path_from(start, end, last, 1 << start)
+    }
+
+    fn longest_simple_path_from(&self, current: usize, end: usize, last: Option<usize>, visited: u128) -> Option<u64> {
+        if current == end {
+            return Some(0);
+        }
+        self.edges[current].iter()
+            .filter(|(next, _)| visited & (1 << next) == 0)
+            .filter(|(next, _)| last != Some(current) || *next == end)
+            .filter_map(|(next, weight)| {
+                self.longest_simple_path_from(*next, end, last, visited | (1 << next)).map(|it| it + weight)
+            })
+            .max()
+    }
+
+    /// Finds a global minimum cut with the Stoer-Wagner algorithm, treating every edge as undirected.
+    /// Returns the total weight of the cut edges and the nodes on one side of the cut,
+    /// or `None` if the graph has less than two nodes.
+    ///
+    /// See <https://en.wikipedia.org/wiki/Stoer%E2%80%93Wagner_algorithm>
+    ///
+    pub fn min_cut(&self) -> Option<(u64, Vec<usize>)> {
+        if self.len() < 2 {
+            return None;
+        }
+        let mut adjacency: Vec<HashMap<usize, u64>> = vec![HashMap::new(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, weight) in edges.iter().filter(|(to, _)| *to != from) {
+                *adjacency[from].entry(*to).or_default() += weight;
+                *adjacency[*to].entry(from).or_default() += weight;
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = self.nodes().map(|it| vec![it]).collect();
+        let mut active: Vec<usize> = self.nodes().collect();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            // maximum adjacency ordering, the last two added nodes are merged afterwards
+            let mut weights = vec![0; self.len()];
+            let mut added = vec![false; self.len()];
+            let mut queue = BinaryHeap::from([(0, active[0])]);
+            let (mut previous, mut last, mut cut) = (active[0], active[0], 0);
+            let mut count = 0;
+            while let Some((weight, node)) = queue.pop() {
+                if added[node] || weight != weights[node] {
+                    continue;
+                }
+                added[node] = true;
+                count += 1;
+                (previous, last, cut) = (last, node, weight);
+                for (next, edge_weight) in &adjacency[node] {
+                    if !added[*next] {
+                        weights[*next] += edge_weight;
+                        queue.push((weights[*next], *next));
+                    }
+                }
+            }
+            if count < active.len() {
+                // the graph is disconnected, the reached component can be cut off for free
+                let side = active.iter().filter(|it| added[**it]).flat_map(|it| groups[*it].clone()).collect();
+                return Some((0, side));
+            }
+            if best.as_ref().is_none_or(|(weight, _)| cut < *weight) {
+                best = Some((cut, groups[last].clone()));
+            }
+
+            let merged = std::mem::take(&mut groups[last]);
+            groups[previous].extend(merged);
+            for (next, weight) in std::mem::take(&mut adjacency[last]) {
+                adjacency[next].remove(&last);
+                if next != previous {
+                    *adjacency[previous].entry(next).or_default() += weight;
+                    *adjacency[next].entry(previous).or_default() += weight;
+                }
+            }
+            active.retain(|it| *it != last);
+        }
+        best
+    }
+}
+
+impl<L: Clone + Eq + Hash + Display> Graph<L> {
+    /// Renders the graph in the DOT language of Graphviz, e.g. for `dot -Tsvg graph.dot > graph.svg`.
+    /// Weights other than 1 are shown as edge labels.
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for (from, edges) in self.edges.iter().enumerate() {
+            if edges.is_empty() {
+                writeln!(dot, "    \"{}\";", self.labels[from]).unwrap();
+            }
+            for (to, weight) in edges {
+                write!(dot, "    \"{}\" -> \"{}\"", self.labels[from], self.labels[*to]).unwrap();
+                if *weight != 1 {
+                    write!(dot, " [label={weight}]").unwrap();
+                }
+                dot.push_str(";\n");
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+/// Parses an edge list with one source node per line, followed by its targets, e.g.
+/// `AAA = (BBB, CCC)`, `a: b c d` or `a -> b, c`. Every edge has weight 1.
+/// The targets keep the order of the line, so `successors(id)[0]` is the first target.
+///
+/// ```
+/// # use advent_of_code::util::graph::parse_edges;
+/// let graph = parse_edges("AAA = (BBB, CCC)\nBBB = (DDD, EEE)").unwrap();
+/// let aaa = graph.id(&"AAA").unwrap();
+/// assert_eq!(graph.label(graph.successors(aaa)[1].0), &"CCC");
+/// ```
+///
+pub fn parse_edges(input: &str) -> Result<Graph<&str>, SolutionError> {
+    let mut graph = Graph::new();
+    map_lines(input, |line| {
+        let (source, targets) = line.split_once("->")
+            .or_else(|| line.split_once(['=', ':']))
+            .ok_or_else(|| SolutionError::near(line, "expected \"source = targets\", \"source: targets\" or \"source -> targets\""))?;
+        let source = graph.intern(source.trim());
+        targets
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|it| !it.is_empty())
+            .for_each(|target| {
+                let target = graph.intern(target);
+                graph.edges[source].push((target, 1));
+            });
+        Ok(())
+    })?;
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut components: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+        components.iter_mut().for_each(|it| it.sort());
+        components.sort();
+        components
+    }
+
+    #[test]
+    fn parses_edge_lists() {
+        let graph = parse_edges("AAA = (BBB, BBB)\nBBB: AAA ZZZ\nZZZ -> ZZZ\n").unwrap();
+        assert_eq!(graph.len(), 3);
+        let aaa = graph.id(&"AAA").unwrap();
+        assert_eq!(graph.successors(aaa).len(), 2);
+        assert_eq!(graph.bfs(aaa).distance(&graph.id(&"ZZZ").unwrap()), Some(2));
+        assert!(parse_edges("AAA BBB").is_err());
+    }
+
+    #[test]
+    fn dijkstra_uses_weights() {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b", 1);
+        graph.add_edge("b", "c", 1);
+        graph.add_edge("a", "c", 5);
+        let search = graph.dijkstra(0);
+        assert_eq!(search.distance(&graph.id(&"c").unwrap()), Some(2));
+        assert_eq!(search.path_to(&2).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = parse_edges("shirt: tie belt\ntie: jacket\npants: shoes belt\nbelt: jacket").unwrap();
+        let order = graph.topological_sort().unwrap();
+        let position = |label| order.iter().position(|it| *graph.label(*it) == label).unwrap();
+        assert!(position("shirt") < position("tie") && position("tie") < position("jacket"));
+        assert!(position("pants") < position("belt") && position("belt") < position("jacket"));
+        assert!(parse_edges("a: b\nb: a").unwrap().topological_sort().is_none());
+    }
+
+    #[test]
+    fn finds_strongly_connected_components() {
+        let graph = parse_edges("0: 1\n1: 2\n2: 0 3\n3: 4\n4: 5\n5: 3\n6: 5").unwrap();
+        let components = graph.strongly_connected_components()
+            .into_iter()
+            .map(|it| it.into_iter().map(|id| graph.label(id).parse().unwrap()).collect())
+            .collect();
+        assert_eq!(sorted(components), vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    }
+
+    #[test]
+    fn longest_path_in_dag() {
+        let mut graph = Graph::new();
+        graph.add_edge("s", "a", 1);
+        graph.add_edge("s", "b", 4);
+        graph.add_edge("a", "b", 5);
+        graph.add_edge("b", "t", 1);
+        graph.add_edge("a", "t", 2);
+        let (s, t) = (graph.id(&"s").unwrap(), graph.id(&"t").unwrap());
+        assert_eq!(graph.longest_path(s, t), Some(7));
+        assert_eq!(graph.longest_path(t, s), None);
+    }
+
+    #[test]
+    fn longest_simple_path_with_cycles() {
+        let mut graph = Graph::new();
+        graph.add_undirected_edge("s", "a", 1);
+        graph.add_undirected_edge("a", "b", 5);
+        graph.add_undirected_edge("b", "c", 1);
+        graph.add_undirected_edge("a", "c", 1);
+        graph.add_undirected_edge("c", "t", 1);
+        let (s, t) = (graph.id(&"s").unwrap(), graph.id(&"t").unwrap());
+        assert_eq!(graph.longest_simple_path(s, t), Some(8));
+        assert_eq!(graph.longest_path(s, t), None);
+        graph.intern("u");
+        assert_eq!(graph.longest_simple_path(s, graph.id(&"u").unwrap()), None);
+    }
+
+    #[test]
+    fn minimum_cut() {
+        // two triangles connected by a single bridge
+        let graph = parse_edges("a: b c\nb: c\nc: d\nd: e f\ne: f").unwrap();
+        let (weight, mut side) = graph.min_cut().unwrap();
+        side.sort();
+        assert_eq!(weight, 1);
+        assert!(side == vec![0, 1, 2] || side == vec![3, 4, 5], "{side:?}");
+        assert_eq!(parse_edges("a: b\nc: d").unwrap().min_cut().unwrap().0, 0);
+    }
+
+    #[test]
+    fn renders_dot() {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b", 1);
+        graph.add_edge("b", "a", 3);
+        assert_eq!(graph.to_dot(), "digraph {\n    \"a\" -> \"b\";\n    \"b\" -> \"a\" [label=3];\n}");
+    }
+}
@@ -1,7 +1,7 @@
 use crate::util::position::Position;
 use std::fmt::{Display, Formatter};
 
-#[derive(Eq, PartialEq, Hash, Debug)]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Grid2d<T> {
     content: Vec<Vec<T>>,
 }
@@ -22,6 +22,13 @@ impl<T> Grid2d<T> {
     pub fn size(&self) -> (usize, usize) {
         (self.content[0].len(), self.content.len())
     }
+
+    /// Returns all positions of the grid row by row.
+    ///
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let (width, height) = self.size();
+        (0..height).flat_map(move |y| (0..width).map(move |x| Position::from((x, y))))
+    }
 }
 
 impl<T: Copy + PartialEq> Grid2d<T> {
@@ -0,0 +1,514 @@
+//! Export of grids, paths and simulation frames as images, e.g. for writeups.
+//!
+//! Raster images are written as PPM, PNG or animated GIF, vector drawings as SVG.
+//! All encoders are implemented here without compression libraries: PNG uses uncompressed deflate blocks
+//! and GIF its own LZW compression. The files go to `data/viz/NN/`, see [`write`].
+
+use crate::template::Day;
+use crate::util::grid2d::Grid2d;
+use crate::util::position::Position;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// Formats the color for SVG, e.g. `#ff8000`.
+    ///
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Maps the values of grid cells to colors, values without a color get the default one.
+///
+/// ```
+/// # use advent_of_code::util::image::{Palette, Rgb};
+/// let palette = Palette::new(Rgb::WHITE).with('#', Rgb::BLACK).with('O', Rgb(200, 80, 0));
+/// assert_eq!((palette.color(&'#'), palette.color(&'.')), (Rgb::BLACK, Rgb::WHITE));
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Palette<T> {
+    colors: HashMap<T, Rgb>,
+    default: Rgb,
+}
+
+impl<T: Eq + Hash> Palette<T> {
+    pub fn new(default: Rgb) -> Self {
+        Palette { colors: HashMap::new(), default }
+    }
+
+    pub fn with(mut self, value: T, color: Rgb) -> Self {
+        self.colors.insert(value, color);
+        self
+    }
+
+    pub fn color(&self, value: &T) -> Rgb {
+        self.colors.get(value).copied().unwrap_or(self.default)
+    }
+}
+
+/// A raster image, every grid cell is drawn as a square of `scale` pixels.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    scale: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Creates an image for a grid of `width` by `height` cells filled with `background`.
+    ///
+    pub fn new(width: usize, height: usize, scale: usize, background: Rgb) -> Self {
+        assert!(scale > 0, "the scale must be positive");
+        Image { width: width * scale, height: height * scale, scale, pixels: vec![background; width * height * scale * scale] }
+    }
+
+    /// Draws every cell of `grid` in the color the `palette` assigns to its value.
+    ///
+    pub fn of_grid<T: Copy + Eq + Hash>(grid: &Grid2d<T>, palette: &Palette<T>, scale: usize) -> Self {
+        let (width, height) = grid.size();
+        let mut image = Image::new(width, height, scale, palette.default);
+        grid.positions().for_each(|it| image.fill(&it, palette.color(&grid.get(&it))));
+        image
+    }
+
+    /// Returns the size in pixels as `(width, height)`.
+    ///
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the color of the pixel at `(x, y)`.
+    ///
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Fills the square of the cell at `position`, positions outside the image are ignored.
+    ///
+    pub fn fill(&mut self, position: &Position, color: Rgb) {
+        let (x, y) = (position.x * self.scale as i64, position.y * self.scale as i64);
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        for row in y as usize..y as usize + self.scale {
+            let start = row * self.width + x as usize;
+            self.pixels[start..start + self.scale].fill(color);
+        }
+    }
+
+    /// Fills the cells of `path`, e.g. the loop of day 10 on top of its grid.
+    ///
+    pub fn draw_path<'a>(&mut self, path: impl IntoIterator<Item = &'a Position>, color: Rgb) {
+        path.into_iter().for_each(|it| self.fill(it, color));
+    }
+
+    /// Encodes the image as binary PPM (P6).
+    ///
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flat_map(|it| [it.0, it.1, it.2]));
+        bytes
+    }
+
+    /// Encodes the image as PNG with 8 bit RGB pixels.
+    ///
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width) {
+            // every scanline starts with its filter type, 0 means unfiltered
+            raw.push(0);
+            raw.extend(row.iter().flat_map(|it| [it.0, it.1, it.2]));
+        }
+
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, color type RGB, default compression, filter and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(kind.iter().chain(data));
+    png.extend(crc.to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks.
+///
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = if data.is_empty() { vec![&[]] } else { data.chunks(u16::MAX as usize).collect() };
+    for (i, block) in blocks.iter().enumerate() {
+        stream.push((i == blocks.len() - 1) as u8);
+        stream.extend((block.len() as u16).to_le_bytes());
+        stream.extend((!(block.len() as u16)).to_le_bytes());
+        stream.extend(*block);
+    }
+    let (a, b) = data.iter().fold((1_u32, 0_u32), |(a, b), it| {
+        let a = (a + *it as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    stream.extend(((b << 16) | a).to_be_bytes());
+    stream
+}
+
+/// Encodes `frames` as an animated GIF which loops forever, showing each frame for `delay` hundredths of a second.
+/// Returns `None` if there are no frames, their sizes differ, or they use more than 256 colors.
+///
+pub fn to_gif(frames: &[Image], delay: u16) -> Option<Vec<u8>> {
+    let (width, height) = frames.first()?.size();
+    if frames.iter().any(|it| it.size() != (width, height)) {
+        return None;
+    }
+    let mut colors: Vec<Rgb> = Vec::new();
+    let mut indices: HashMap<Rgb, u8> = HashMap::new();
+    for pixel in frames.iter().flat_map(|it| &it.pixels) {
+        if !indices.contains_key(pixel) {
+            indices.insert(*pixel, u8::try_from(colors.len()).ok()?);
+            colors.push(*pixel);
+        }
+    }
+    // the color table has 2^(bits) entries, with at least two bits as required by the LZW minimum code size
+    let bits = (usize::BITS - (colors.len() - 1).leading_zeros()).max(2);
+    colors.resize(1 << bits, Rgb::BLACK);
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend((width as u16).to_le_bytes());
+    gif.extend((height as u16).to_le_bytes());
+    gif.extend([0xf0 | (bits as u8 - 1), 0, 0]);
+    gif.extend(colors.iter().flat_map(|it| [it.0, it.1, it.2]));
+    // application extension to loop the animation forever
+    gif.extend([0x21, 0xff, 0x0b]);
+    gif.extend(b"NETSCAPE2.0");
+    gif.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for frame in frames {
+        gif.extend([0x21, 0xf9, 0x04, 0x00]);
+        gif.extend(delay.to_le_bytes());
+        gif.extend([0x00, 0x00]);
+        gif.push(0x2c);
+        gif.extend([0, 0, 0, 0]);
+        gif.extend((width as u16).to_le_bytes());
+        gif.extend((height as u16).to_le_bytes());
+        gif.push(0);
+        gif.push(bits as u8);
+        let data = lzw(frame.pixels.iter().map(|it| indices[it]), bits);
+        for block in data.chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend(block);
+        }
+        gif.push(0);
+    }
+    gif.push(0x3b);
+    Some(gif)
+}
+
+/// Collects variable length codes into bytes, least significant bit first.
+///
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u32) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compresses color indices with the LZW variant of GIF.
+///
+fn lzw(indices: impl IntoIterator<Item = u8>, minimum_size: u32) -> Vec<u8> {
+    const MAX_CODE: u16 = 4096;
+    let clear = 1_u16 << minimum_size;
+    let end = clear + 1;
+    let mut writer = BitWriter { bytes: Vec::new(), buffer: 0, bits: 0 };
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut size = minimum_size + 1;
+    writer.write(clear, size);
+
+    let mut current: Option<u16> = None;
+    for index in indices {
+        let Some(prefix) = current else {
+            current = Some(index as u16);
+            continue;
+        };
+        if let Some(code) = codes.get(&(prefix, index)) {
+            current = Some(*code);
+            continue;
+        }
+        writer.write(prefix, size);
+        if next < MAX_CODE {
+            codes.insert((prefix, index), next);
+            next += 1;
+            // the decoder adds its entries one code later, so the size grows once the next code does not fit
+            if next > 1 << size {
+                size += 1;
+            }
+        } else {
+            writer.write(clear, size);
+            codes.clear();
+            next = end + 1;
+            size = minimum_size + 1;
+        }
+        current = Some(index as u16);
+    }
+    if let Some(prefix) = current {
+        writer.write(prefix, size);
+    }
+    writer.write(end, size);
+    writer.finish()
+}
+
+/// A vector drawing of grids and polygons in grid coordinates, every cell is `scale` units wide.
+///
+/// ```
+/// # use advent_of_code::util::image::{Rgb, Svg};
+/// # use advent_of_code::util::position::Position;
+/// let square = [Position::at(0, 0), Position::at(2, 0), Position::at(2, 2), Position::at(0, 2)];
+/// let svg = Svg::new(10).polygon(&square, Rgb(0, 0, 255), Some(Rgb::WHITE)).to_string();
+/// assert!(svg.contains(r#"<polygon points="0,0 20,0 20,20 0,20""#));
+/// ```
+///
+pub struct Svg {
+    scale: i64,
+    min: Position,
+    max: Position,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    pub fn new(scale: i64) -> Self {
+        Svg { scale, min: Position::at(i64::MAX, i64::MAX), max: Position::at(i64::MIN, i64::MIN), elements: Vec::new() }
+    }
+
+    fn include(&mut self, position: &Position) {
+        self.min = Position::at(self.min.x.min(position.x), self.min.y.min(position.y));
+        self.max = Position::at(self.max.x.max(position.x), self.max.y.max(position.y));
+    }
+
+    /// Draws every cell of `grid` whose value has a color in the `palette` as a square, the other cells are left out.
+    ///
+    pub fn grid<T: Copy + Eq + Hash>(mut self, grid: &Grid2d<T>, palette: &Palette<T>) -> Self {
+        let (width, height) = grid.size();
+        self.include(&Position::at(0, 0));
+        self.include(&Position::at(width as i64, height as i64));
+        for position in grid.positions() {
+            if let Some(color) = palette.colors.get(&grid.get(&position)) {
+                self.elements.push(format!(
+                    r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#,
+                    position.x * self.scale, position.y * self.scale, color.hex(), s = self.scale,
+                ));
+            }
+        }
+        self
+    }
+
+    fn points(&mut self, points: &[Position]) -> String {
+        points.iter().for_each(|it| self.include(it));
+        points.iter().map(|it| format!("{},{}", it.x * self.scale, it.y * self.scale)).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Draws a closed polygon through `vertices`, e.g. the lagoon of day 18.
+    /// The outline keeps its width however far the drawing is zoomed out.
+    ///
+    pub fn polygon(mut self, vertices: &[Position], stroke: Rgb, fill: Option<Rgb>) -> Self {
+        let points = self.points(vertices);
+        let fill = fill.map_or("none".to_string(), |it| it.hex());
+        self.elements.push(format!(r#"<polygon points="{points}" fill="{fill}" stroke="{}" vector-effect="non-scaling-stroke"/>"#, stroke.hex()));
+        self
+    }
+
+    /// Draws an open line through the centers of the cells of `path`.
+    ///
+    pub fn path(mut self, path: &[Position], stroke: Rgb) -> Self {
+        let centered: Vec<String> = path.iter()
+            .map(|it| {
+                self.include(it);
+                self.include(&Position::at(it.x + 1, it.y + 1));
+                format!("{},{}", it.x * self.scale + self.scale / 2, it.y * self.scale + self.scale / 2)
+            })
+            .collect();
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            centered.join(" "), stroke.hex(), (self.scale / 4).max(1),
+        ));
+        self
+    }
+}
+
+impl std::fmt::Display for Svg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (min, max) = if self.elements.is_empty() { (Position::at(0, 0), Position::at(0, 0)) } else { (self.min, self.max) };
+        let (x, y) = (min.x * self.scale, min.y * self.scale);
+        let (width, height) = ((max.x - min.x) * self.scale, (max.y - min.y) * self.scale);
+        // the stroke of outlines is centered on the border, so it needs a small margin
+        let margin = (self.scale / 4).max(1);
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            x - margin, y - margin, width + 2 * margin, height + 2 * margin,
+        );
+        svg.push('\n');
+        for element in &self.elements {
+            writeln!(svg, "  {element}")?;
+        }
+        svg.push_str("</svg>\n");
+        f.write_str(&svg)
+    }
+}
+
+/// Returns the path of a file in `data/viz/NN/`.
+///
+pub fn viz_path(day: Day, name: &str) -> PathBuf {
+    env::current_dir().unwrap().join("data").join("viz").join(day.to_string()).join(name)
+}
+
+/// Writes `contents` to `data/viz/NN/name`, creating the folders if needed, and returns the path of the file.
+///
+pub fn write(day: Day, name: &str, contents: impl AsRef<[u8]>) -> io::Result<PathBuf> {
+    let path = viz_path(day, name);
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes the LZW data of a GIF frame, the counterpart of [`lzw`].
+    fn unlzw(data: &[u8], minimum_size: u32) -> Vec<u8> {
+        let clear = 1_usize << minimum_size;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut size = minimum_size + 1;
+        let (mut buffer, mut bits, mut bytes) = (0_u32, 0, data.iter());
+        let mut previous: Option<usize> = None;
+        let mut result = Vec::new();
+        loop {
+            while bits < size {
+                buffer |= (*bytes.next().unwrap() as u32) << bits;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << size) - 1)) as usize;
+            buffer >>= size;
+            bits -= size;
+            if code == clear {
+                table = (0..clear).map(|it| vec![it as u8]).chain([vec![], vec![]]).collect();
+                size = minimum_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return result;
+            }
+            let entry = match (table.get(code), previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [table[previous].clone(), vec![table[previous][0]]].concat(),
+                (None, None) => panic!("invalid code {code}"),
+            };
+            if let Some(previous) = previous {
+                table.push([table[previous].clone(), vec![entry[0]]].concat());
+            }
+            if table.len() == 1 << size && size < 12 {
+                size += 1;
+            }
+            result.extend(&entry);
+            previous = Some(code);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let mut state = 7_u32;
+        let noisy: Vec<u8> = (0..20_000).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state % 7 % 4) as u8
+        }).collect();
+        let repetitive: Vec<u8> = (0..20_000).map(|it| (it / 100 % 3) as u8).collect();
+        for indices in [noisy, repetitive, vec![1], vec![]] {
+            assert_eq!(unlzw(&lzw(indices.iter().copied(), 2), 2), indices);
+        }
+    }
+
+    #[test]
+    fn encodes_png() {
+        let grid = Grid2d::of_lines("#.\n.#");
+        let image = Image::of_grid(&grid, &Palette::new(Rgb::WHITE).with('#', Rgb::BLACK), 2);
+        assert_eq!(image.size(), (4, 4));
+        assert_eq!((image.pixel(1, 1), image.pixel(2, 1), image.pixel(3, 3)), (Rgb::BLACK, Rgb::WHITE, Rgb::BLACK));
+        let png = image.to_png();
+        assert_eq!(&png[12..16], b"IHDR");
+        // the CRC of the IEND chunk is fixed
+        assert_eq!(&png[png.len() - 8..], &[b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]);
+        assert_eq!(&image.to_ppm()[..11], b"P6\n4 4\n255\n");
+    }
+
+    #[test]
+    fn zlib_checksum() {
+        // known Adler-32 of "Wikipedia"
+        let stream = zlib_stored(b"Wikipedia");
+        assert_eq!(&stream[stream.len() - 4..], &0x11e6_0398_u32.to_be_bytes());
+        assert_eq!(zlib_stored(&[]), vec![0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn gif_requires_matching_frames() {
+        let a = Image::new(2, 2, 1, Rgb::WHITE);
+        let b = Image::new(3, 2, 1, Rgb::WHITE);
+        assert!(to_gif(&[], 10).is_none());
+        assert!(to_gif(&[a.clone(), b], 10).is_none());
+        let gif = to_gif(&[a.clone(), a], 10).unwrap();
+        assert!(gif.starts_with(b"GIF89a") && gif.ends_with(&[0x3b]));
+    }
+}
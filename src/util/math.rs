@@ -0,0 +1,340 @@
+//! Number theory helpers for combining cycles and solving equations over the integers.
+
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Rem, RangeInclusive, Sub};
+
+/// The integer types the generic helpers of this module work with.
+///
+pub trait Integer:
+    Copy + Ord + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self> + From<u8>
+{
+}
+
+impl<T> Integer for T where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T> + From<u8>
+{
+}
+
+fn abs<T: Integer>(value: T) -> T {
+    let zero = T::from(0);
+    if value < zero { zero - value } else { value }
+}
+
+/// Calculates the greatest common divisor, which is never negative. `gcd(0, 0)` is 0.
+///
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::from(0) {
+        (a, b) = (b, a % b);
+    }
+    abs(a)
+}
+
+/// Calculates the least common multiple, which is never negative. The result is 0 if one of the values is 0.
+///
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::from(0) || b == T::from(0) {
+        return T::from(0);
+    }
+    abs(a / gcd(a, b) * b)
+}
+
+/// Calculates the greatest common divisor of all `values`, 0 if there are none.
+///
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::from(0), gcd)
+}
+
+/// Calculates the least common multiple of all `values`, 1 if there are none.
+/// This is the first step at which cycles of the given lengths, all starting at step 0, line up again.
+///
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::from(1), lcm)
+}
+
+/// Extended Euclidean algorithm, returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`.
+///
+/// See <https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm>
+///
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 { (-old_r, -old_x, -old_y) } else { (old_r, old_x, old_y) }
+}
+
+/// Calculates the inverse of `a` modulo `modulus`, if `a` and `modulus` are coprime.
+///
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Calculates `base^exponent mod modulus` by repeated squaring.
+///
+pub fn mod_pow(base: u64, exponent: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+    let modulus = modulus as u128;
+    let (mut result, mut base, mut exponent) = (1_u128, base as u128 % modulus, exponent);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Solves a system of congruences `x ≡ remainder (mod modulus)` with the Chinese Remainder Theorem.
+/// The moduli need not be coprime. Returns the smallest non-negative solution together with the
+/// modulus of the combined congruence, i.e. the lcm of all moduli, or `None` if the system has no solution.
+///
+/// ```
+/// # use advent_of_code::util::math::crt;
+/// assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt([(1, 4), (2, 6)]), None);
+/// ```
+///
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences.into_iter().try_fold((0, 1), |(remainder, modulus), (r, m)| {
+        let (g, x, _) = extended_gcd(modulus, m);
+        let difference = r - remainder;
+        if difference % g != 0 {
+            return None;
+        }
+        let combined = modulus / g * m;
+        let step = (difference / g * x).rem_euclid(m / g);
+        Some(((remainder + modulus * step).rem_euclid(combined), combined))
+    })
+}
+
+/// Calculates the integer square root, i.e. the largest `r` with `r * r <= n`.
+///
+pub fn isqrt(n: u128) -> u128 {
+    // the floating point estimate is off by at most a few units, which are corrected below
+    let mut root = (n as f64).sqrt() as u128;
+    while root.checked_mul(root).is_none_or(|it| it > n) {
+        root -= 1;
+    }
+    while (root + 1).checked_mul(root + 1).is_some_and(|it| it <= n) {
+        root += 1;
+    }
+    root
+}
+
+/// Returns the integers `x` with `a * x² + b * x + c < 0`, which form a single range for `a > 0`.
+/// The bounds are calculated exactly, without floating point rounding errors at the roots.
+///
+/// ```
+/// # use advent_of_code::util::math::quadratic_below_zero;
+/// // x * (7 - x) > 9 <=> x² - 7x + 9 < 0
+/// assert_eq!(quadratic_below_zero(1, -7, 9), Some(2..=5));
+/// assert_eq!(quadratic_below_zero(1, 0, 0), None);
+/// ```
+///
+/// # Panics
+/// Panics if `a` is not positive.
+///
+pub fn quadratic_below_zero(a: i64, b: i64, c: i64) -> Option<RangeInclusive<i64>> {
+    assert!(a > 0, "the quadratic must open upwards");
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    let value = |x: i128| a * x * x + b * x + c;
+
+    let discriminant = b * b - 4 * a * c;
+    if discriminant <= 0 {
+        return None;
+    }
+    let root = isqrt(discriminant as u128) as i128;
+    let mut low = (-b - root).div_euclid(2 * a);
+    let mut high = (-b + root).div_euclid(2 * a);
+    while value(low) >= 0 && low <= high {
+        low += 1;
+    }
+    while value(low - 1) < 0 {
+        low -= 1;
+    }
+    while value(high) >= 0 && high >= low {
+        high -= 1;
+    }
+    while value(high + 1) < 0 {
+        high += 1;
+    }
+    (low <= high).then_some(low as i64..=high as i64)
+}
+
+/// An exact fraction, always stored in lowest terms with a positive denominator.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    /// # Panics
+    /// Panics if `denominator` is 0.
+    ///
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "denominator must not be 0");
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self { numerator: numerator / divisor, denominator: denominator / divisor }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    /// Returns the value if it is an integer.
+    ///
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self { numerator: value as i128, denominator: 1 }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        let denominator = lcm(self.denominator, other.denominator);
+        Rational::new(
+            self.numerator * (denominator / self.denominator) + other.numerator * (denominator / other.denominator),
+            denominator,
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational { numerator: -self.numerator, denominator: self.denominator }
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        // cross-cancel first to keep the intermediate products small
+        let a = gcd(self.numerator, other.denominator).max(1);
+        let b = gcd(other.numerator, self.denominator).max(1);
+        Rational::new(
+            (self.numerator / a) * (other.numerator / b),
+            (self.denominator / b) * (other.denominator / a),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// # Panics
+    /// Panics if `other` is 0.
+    fn div(self, other: Rational) -> Rational {
+        self.mul(Rational::new(other.denominator, other.numerator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12_u64, 18), 6);
+        assert_eq!(gcd(-12_i64, 18), 6);
+        assert_eq!(gcd(0_u32, 0), 0);
+        assert_eq!(lcm(4_i32, -6), 12);
+        assert_eq!(lcm_all([2_u64, 3, 4, 5]), 60);
+        assert_eq!(gcd_all([12_u64, 18, 27]), 3);
+        assert_eq!(lcm_all(Vec::<u64>::new()), 1);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn crt_matches_brute_force() {
+        for (r1, m1, r2, m2) in [(0, 4, 2, 6), (3, 9, 6, 12), (1, 5, 4, 7), (2, 4, 1, 6)] {
+            let expected = (0..m1 * m2).find(|x| x % m1 == r1 && x % m2 == r2);
+            assert_eq!(crt([(r1, m1), (r2, m2)]).map(|it| it.0), expected);
+        }
+    }
+
+    #[test]
+    fn integer_square_root() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        let big = u64::MAX as u128;
+        assert_eq!(isqrt(big * big), big);
+        assert_eq!(isqrt(big * big - 1), big - 1);
+    }
+
+    #[test]
+    fn quadratic_bounds_match_brute_force() {
+        for (a, b, c) in [(1, -7, 9), (1, -30, 200), (2, -3, -5), (3, 0, -27), (1, -71530, 940200)] {
+            let expected: Vec<_> = (-100_000..100_000).filter(|x| a * x * x + b * x + c < 0).collect();
+            let actual: Vec<_> = quadratic_below_zero(a, b, c).into_iter().flatten().collect();
+            assert_eq!(actual, expected, "{a}x² + {b}x + {c}");
+        }
+    }
+
+    #[test]
+    fn rational_arithmetic() {
+        let half = Rational::new(2, -4);
+        assert_eq!((half.numerator(), half.denominator()), (-1, 2));
+        assert_eq!(Rational::new(1, 3) + Rational::new(1, 6), Rational::new(1, 2));
+        assert_eq!(Rational::new(1, 3) - Rational::new(1, 2), Rational::new(-1, 6));
+        assert_eq!(Rational::new(3, 4) * Rational::new(2, 9), Rational::new(1, 6));
+        assert_eq!(Rational::new(3, 4) / Rational::new(3, 8), Rational::from(2));
+        assert_eq!(Rational::from(0) * Rational::new(5, 7), Rational::from(0));
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(Rational::new(-7, 2).to_string(), "-7/2");
+    }
+}
@@ -0,0 +1,144 @@
+//! Contraction of corridor mazes in a [`Grid2d`] into small weighted graphs.
+//!
+//! Corridors without branches are replaced by a single edge whose weight is the number of steps,
+//! so searches only have to decide at the junctions.
+
+use crate::util::graph::Graph;
+use crate::util::grid2d::Grid2d;
+use crate::util::position::{Direction, Position};
+
+/// A maze of open cells and walls, where slopes like `>` or `v` can optionally only be crossed downhill.
+///
+pub struct Maze<'a> {
+    grid: &'a Grid2d<char>,
+    wall: char,
+    one_way_slopes: bool,
+}
+
+impl<'a> Maze<'a> {
+    /// Creates a maze with `#` as walls, whose slopes can be crossed in any direction.
+    ///
+    pub fn new(grid: &'a Grid2d<char>) -> Self {
+        Maze { grid, wall: '#', one_way_slopes: false }
+    }
+
+    pub fn wall(mut self, wall: char) -> Self {
+        self.wall = wall;
+        self
+    }
+
+    /// Only allows to leave the slopes `^`, `>`, `v` and `<` in the direction they point to.
+    ///
+    pub fn one_way_slopes(mut self, one_way_slopes: bool) -> Self {
+        self.one_way_slopes = one_way_slopes;
+        self
+    }
+
+    pub fn is_open(&self, position: &Position) -> bool {
+        position.is_within(self.grid) && self.grid.get(position) != self.wall
+    }
+
+    fn open_neighbors(&self, position: &Position) -> impl Iterator<Item = Position> + '_ {
+        position.neighbors4().filter(|it| self.is_open(it))
+    }
+
+    pub fn can_move(&self, from: &Position, to: &Position) -> bool {
+        match Direction::try_from(self.grid.get(from)) {
+            Ok(direction) if self.one_way_slopes => from + &direction.to_position() == *to,
+            _ => true,
+        }
+    }
+
+    /// Returns the open cells with more than two open neighbors, where a path can branch.
+    ///
+    pub fn junctions(&self) -> Vec<Position> {
+        let (width, height) = self.grid.size();
+        (0..height as i64)
+            .flat_map(|y| (0..width as i64).map(move |x| Position { x, y }))
+            .filter(|it| self.is_open(it) && self.open_neighbors(it).count() > 2)
+            .collect()
+    }
+
+    /// Contracts the maze into a graph of its junctions and the given `points`, e.g. the start and the end.
+    /// The points are interned first, in the given order, so their ids are `0..points.len()`.
+    /// Every corridor between two nodes becomes an edge weighted with its length,
+    /// corridors ending in a dead end or against a slope are dropped.
+    ///
+    /// ```
+    /// # use advent_of_code::util::grid2d::Grid2d;
+    /// # use advent_of_code::util::maze::Maze;
+    /// # use advent_of_code::util::position::Position;
+    /// let grid = Grid2d::of_lines("#.###\n#...#\n#.#.#\n#...#\n###.#");
+    /// let (start, end) = (Position { x: 1, y: 0 }, Position { x: 3, y: 4 });
+    /// let graph = Maze::new(&grid).contract(&[start, end]);
+    /// assert_eq!(graph.len(), 4);
+    /// assert_eq!(graph.longest_simple_path(0, 1), Some(6));
+    /// ```
+    ///
+    pub fn contract(&self, points: &[Position]) -> Graph<Position> {
+        let mut graph = Graph::new();
+        points.iter().for_each(|it| { graph.intern(*it); });
+        self.junctions().into_iter().for_each(|it| { graph.intern(it); });
+
+        for node in graph.nodes() {
+            let from = *graph.label(node);
+            let corridors: Vec<(Position, u64)> = self.open_neighbors(&from)
+                .filter(|it| self.can_move(&from, it))
+                .filter_map(|first| self.follow_corridor(&graph, from, first))
+                .collect();
+            corridors.into_iter().for_each(|(to, length)| graph.add_edge(from, to, length));
+        }
+        graph
+    }
+
+    /// Walks the corridor from `from` via `first` until it reaches another node of the graph.
+    ///
+    fn follow_corridor(&self, graph: &Graph<Position>, from: Position, first: Position) -> Option<(Position, u64)> {
+        let mut previous = from;
+        let mut current = first;
+        let mut length = 1;
+        while graph.id(&current).is_none() {
+            let next = self.open_neighbors(&current).find(|it| *it != previous)?;
+            if !self.can_move(&current, &next) {
+                return None;
+            }
+            previous = current;
+            current = next;
+            length += 1;
+        }
+        Some((current, length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contracts_corridors() {
+        let grid = Grid2d::of_lines("#.#####\n#.....#\n#.###.#\n#.....#\n#####.#");
+        let (start, end) = (Position { x: 1, y: 0 }, Position { x: 5, y: 4 });
+        let graph = Maze::new(&grid).contract(&[start, end]);
+        let (top_left, bottom_right) = (graph.id(&Position { x: 1, y: 1 }).unwrap(), graph.id(&Position { x: 5, y: 3 }).unwrap());
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.successors(0), &[(top_left, 1)]);
+        // both sides of the loop connect the junctions
+        assert_eq!(graph.successors(top_left).iter().filter(|(to, _)| *to == bottom_right).count(), 2);
+        assert_eq!(graph.longest_simple_path(0, 1), Some(8));
+        assert_eq!(graph.longest_simple_path(1, 0), Some(8));
+    }
+
+    #[test]
+    fn honors_slopes() {
+        let grid = Grid2d::of_lines("#.###\n#.>.#\n#.#v#\n#...#\n###.#");
+        let (start, end) = (Position { x: 1, y: 0 }, Position { x: 3, y: 4 });
+        let junction = Position { x: 3, y: 3 };
+        let graph = Maze::new(&grid).contract(&[start, end]);
+        assert_eq!(graph.successors(graph.id(&junction).unwrap()).len(), 3);
+        // the right side of the loop can only be walked downhill
+        let one_way = Maze::new(&grid).one_way_slopes(true).contract(&[start, end]);
+        assert_eq!(one_way.successors(one_way.id(&junction).unwrap()).len(), 2);
+        assert_eq!(one_way.longest_simple_path(0, 1), Some(6));
+        assert_eq!(one_way.longest_simple_path(1, 0), Some(6));
+    }
+}
@@ -1,2 +1,19 @@
+pub mod automaton;
+pub mod circuit;
+pub mod cycle;
+pub mod geometry;
+pub mod graph;
 pub mod grid2d;
-pub mod position;
\ No newline at end of file
+pub mod image;
+pub mod math;
+pub mod maze;
+pub mod parse;
+pub mod position;
+pub mod position3;
+pub mod posn;
+pub mod ranges;
+pub mod rules;
+pub mod search;
+pub mod sequence;
+pub mod trace;
+pub mod viz;
@@ -0,0 +1,229 @@
+//! Helpers for the recurring parsing tasks of puzzle inputs.
+//! The helpers borrow from the input instead of allocating, and report errors via [`SolutionError::near`],
+//! so the runner can cite the offending position of the input.
+
+use crate::template::SolutionError;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Returns the slices of `text` that form integers, including a leading `-`.
+/// A `-` directly following a digit is treated as a separator, so `1-3` yields `1` and `3`.
+///
+pub fn number_slices(text: &str) -> impl Iterator<Item = &str> {
+    let bytes = text.as_bytes();
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        while index < bytes.len() && !bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        if index == bytes.len() {
+            return None;
+        }
+        let mut start = index;
+        if start > 0 && bytes[start - 1] == b'-' && (start < 2 || !bytes[start - 2].is_ascii_digit()) {
+            start -= 1;
+        }
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        Some(&text[start..index])
+    })
+}
+
+/// Parses all integers in `text`, see [`number_slices`].
+/// Errors point to numbers which do not fit into `T`, e.g. negative numbers for an unsigned type.
+///
+pub fn try_integers<T>(text: &str) -> impl Iterator<Item = Result<T, SolutionError>> + '_
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    number_slices(text).map(|it| it.parse().map_err(|e| SolutionError::near(it, format!("invalid number \"{it}\": {e}"))))
+}
+
+/// Parses all integers in `text`, see [`number_slices`].
+///
+/// # Panics
+/// Panics if a number does not fit into `T`, use [`try_integers`] to handle that case.
+///
+pub fn integers<T>(text: &str) -> impl Iterator<Item = T> + '_
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    try_integers(text).map(|it| it.unwrap_or_else(|e| panic!("{e}")))
+}
+
+/// Splits `input` into paragraphs separated by one or more blank lines.
+/// The returned blocks do not include the trailing line break of their last line.
+///
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        // skip blank lines before the next block
+        loop {
+            let line_end = rest.find('\n').map_or(rest.len(), |it| it + 1);
+            if rest.is_empty() || !rest[..line_end].trim().is_empty() {
+                break;
+            }
+            rest = &rest[line_end..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut end = 0;
+        let mut content_end = 0;
+        while end < rest.len() {
+            let line_end = rest[end..].find('\n').map_or(rest.len(), |it| end + it + 1);
+            let line = &rest[end..line_end];
+            if line.trim().is_empty() {
+                break;
+            }
+            content_end = end + line.trim_end_matches(['\n', '\r']).len();
+            end = line_end;
+        }
+        let block = &rest[..content_end];
+        rest = &rest[end..];
+        Some(block)
+    })
+}
+
+/// Splits a `key: value` line at the first colon, e.g. `Game 1: 3 blue` into `("Game 1", "3 blue")`.
+/// Whitespace around the key and value is trimmed.
+///
+pub fn key_value(line: &str) -> Result<(&str, &str), SolutionError> {
+    line.split_once(':')
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .ok_or_else(|| SolutionError::near(line, "expected \"key: value\""))
+}
+
+/// Matches `text` against a `pattern` in which every `{}` captures a part of the text.
+/// A capture extends up to the first occurrence of the literal following it, the last capture up to the end of the text.
+///
+/// ```
+/// # use advent_of_code::util::parse::scan;
+/// let [direction, steps, color] = scan("R 6 (#70c710)", "{} {} (#{})").unwrap();
+/// assert_eq!((direction, steps, color), ("R", "6", "70c710"));
+/// ```
+///
+/// # Panics
+/// Panics if the pattern does not contain exactly `N` captures or two captures are not separated by a literal.
+///
+pub fn scan<'a, const N: usize>(text: &'a str, pattern: &str) -> Result<[&'a str; N], SolutionError> {
+    let mut literals = pattern.split("{}");
+    let prefix = literals.next().unwrap();
+    let mut rest = text.strip_prefix(prefix)
+        .ok_or_else(|| SolutionError::near(text, format!("expected \"{prefix}\"")))?;
+
+    let mut captures = [""; N];
+    for (i, capture) in captures.iter_mut().enumerate() {
+        let literal = literals.next()
+            .unwrap_or_else(|| panic!("pattern \"{pattern}\" contains less than {N} captures"));
+        if i == N - 1 {
+            *capture = rest.strip_suffix(literal)
+                .ok_or_else(|| SolutionError::near(rest, format!("expected text ending with \"{literal}\"")))?;
+        } else {
+            assert!(!literal.is_empty(), "captures in pattern \"{pattern}\" must be separated by a literal");
+            let (value, remaining) = rest.split_once(literal)
+                .ok_or_else(|| SolutionError::near(rest, format!("expected \"{literal}\"")))?;
+            *capture = value;
+            rest = remaining;
+        }
+    }
+    assert!(literals.next().is_none(), "pattern \"{pattern}\" contains more than {N} captures");
+    Ok(captures)
+}
+
+/// Parses `text` into `T`, pointing to `text` if it fails.
+///
+pub fn parse_field<T>(text: &str) -> Result<T, SolutionError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.trim().parse().map_err(|e| SolutionError::near(text, format!("invalid value \"{text}\": {e}")))
+}
+
+/// A type which is parsed from a single line of input, see [`parse_lines`].
+///
+pub trait FromLine: Sized {
+    fn from_line(line: &str) -> Result<Self, SolutionError>;
+}
+
+/// Parses every non-empty line of `input` into a `T`.
+/// Errors without a more specific location point to the line that failed.
+///
+pub fn parse_lines<T: FromLine>(input: &str) -> Result<Vec<T>, SolutionError> {
+    map_lines(input, T::from_line)
+}
+
+/// Maps every non-empty line of `input` with `f`.
+/// Errors without a more specific location point to the line that failed.
+///
+pub fn map_lines<'a, T>(input: &'a str, mut f: impl FnMut(&'a str) -> Result<T, SolutionError>) -> Result<Vec<T>, SolutionError> {
+    input.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| f(line).map_err(|e| e.at(input, line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::Location;
+
+    #[test]
+    fn extracts_integers() {
+        let values: Vec<i64> = integers("x=-12, y=3..-4 1-2 -a").collect();
+        assert_eq!(values, vec![-12, 3, -4, 1, 2]);
+        assert_eq!(integers::<u32>("Card  12: 41 48").collect::<Vec<_>>(), vec![12, 41, 48]);
+        assert!(try_integers::<u8>("1 256").any(|it| it.is_err()));
+    }
+
+    #[test]
+    fn splits_blocks() {
+        let input = "a\nb\n\n\nc\r\n\r\nd\n";
+        assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+        assert_eq!(blocks("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn key_values() {
+        assert_eq!(key_value("Game 1: 3 blue").unwrap(), ("Game 1", "3 blue"));
+        assert!(key_value("no colon").is_err());
+    }
+
+    #[test]
+    fn scans_pattern() {
+        let [from, to] = scan("seed-to-soil map:", "{}-to-{} map:").unwrap();
+        assert_eq!((from, to), ("seed", "soil"));
+        let [a, b] = scan("AAA = (BBB, CCC)", "AAA = ({}, {})").unwrap();
+        assert_eq!((a, b), ("BBB", "CCC"));
+        assert!(scan::<3>("AAA = BBB", "{} = ({}, {})").is_err());
+    }
+
+    #[derive(Debug)]
+    struct Move {
+        direction: char,
+        steps: u32,
+    }
+
+    impl FromLine for Move {
+        fn from_line(line: &str) -> Result<Self, SolutionError> {
+            let [direction, steps] = scan(line, "{} {}")?;
+            Ok(Move { direction: parse_field(direction)?, steps: parse_field(steps)? })
+        }
+    }
+
+    #[test]
+    fn parses_lines_with_location() {
+        let moves: Vec<Move> = parse_lines("R 4\nU 2\n").unwrap();
+        assert_eq!(moves.iter().map(|it| (it.direction, it.steps)).collect::<Vec<_>>(), vec![('R', 4), ('U', 2)]);
+
+        let error = parse_lines::<Move>("R 4\nU -2\n").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 2, column: 3 }));
+        let error = parse_lines::<Move>("R 4\nU2\n").unwrap_err();
+        assert_eq!(error.location(), Some(Location { line: 2, column: 1 }));
+    }
+}
@@ -1,5 +1,9 @@
 use crate::util::grid2d::Grid2d;
+use crate::util::posn::PosN;
+use std::error::Error;
+use std::fmt::Display;
 use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Position {
@@ -12,12 +16,149 @@ pub const SOUTH: Position = Position { x: 0, y: 1 };
 pub const EAST: Position = Position { x: 1, y: 0 };
 pub const WEST: Position = Position { x: -1, y: 0 };
 
+/// Moves to the four orthogonal neighbors, in the same order as [`Position::moves`].
+pub const NEIGHBORS4: [Position; 4] = [NORTH, SOUTH, WEST, EAST];
+
+/// Moves to all eight neighbors including diagonals, in the same order as [`Position::moves`].
+pub const NEIGHBORS8: [Position; 8] = [
+    NORTH,
+    SOUTH,
+    WEST,
+    EAST,
+    Position { x: -1, y: -1 },
+    Position { x: -1, y: 1 },
+    Position { x: 1, y: -1 },
+    Position { x: 1, y: 1 },
+];
+
+/// One of the four cardinal directions in a 2-dimensional grid.
+/// North points towards negative `y`, i.e. upwards when the grid is printed.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Returns all directions in clockwise order, starting with [`Direction::North`].
+    ///
+    pub const fn all() -> [Direction; 4] {
+        [Direction::North, Direction::East, Direction::South, Direction::West]
+    }
+
+    /// Returns the direction after turning 90° counterclockwise.
+    ///
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    /// Returns the direction after turning 90° clockwise.
+    ///
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    /// Returns the direction after turning 180°.
+    ///
+    pub const fn opposite(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// Returns the unit move for this direction, e.g. [`NORTH`] for [`Direction::North`].
+    ///
+    pub const fn to_position(self) -> Position {
+        match self {
+            Direction::North => NORTH,
+            Direction::East => EAST,
+            Direction::South => SOUTH,
+            Direction::West => WEST,
+        }
+    }
+}
+
+impl From<Direction> for Position {
+    fn from(value: Direction) -> Self {
+        value.to_position()
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = DirectionFromStrError;
+
+    /// Parses `U`/`D`/`L`/`R`, `N`/`S`/`E`/`W` and the arrows `^`/`v`/`<`/`>` or `↑`/`↓`/`←`/`→`.
+    ///
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' | 'N' | '^' | '↑' => Ok(Direction::North),
+            'R' | 'E' | '>' | '→' => Ok(Direction::East),
+            'D' | 'S' | 'v' | '↓' => Ok(Direction::South),
+            'L' | 'W' | '<' | '←' => Ok(Direction::West),
+            _ => Err(DirectionFromStrError),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = DirectionFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => Err(DirectionFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Direction`].
+#[derive(Debug)]
+pub struct DirectionFromStrError;
+
+impl Error for DirectionFromStrError {}
+
+impl Display for DirectionFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of U, D, L, R, N, S, E, W or an arrow")
+    }
+}
+
 impl From<(usize, usize)> for Position {
     fn from(value: (usize, usize)) -> Self {
         Self { x: value.0 as i64, y: value.1 as i64 }
     }
 }
 
+impl From<PosN<2>> for Position {
+    fn from(value: PosN<2>) -> Self {
+        let [x, y] = value.0;
+        Self { x, y }
+    }
+}
+
+impl From<Position> for PosN<2> {
+    fn from(value: Position) -> Self {
+        PosN([value.x, value.y])
+    }
+}
+
 impl Position {
     pub fn at(x: i64, y: i64) -> Self {
         Self { x, y }
@@ -27,20 +168,7 @@ impl Position {
     /// The moves may include `diagonals` or the `zero_move` depending on the parameters.
     ///
     pub fn moves(diagonals: bool, zero_move: bool) -> Vec<Position> {
-        let mut moves = vec![
-            Position { x: 0, y: -1 },
-            Position { x: 0, y: 1 },
-            Position { x: -1, y: 0 },
-            Position { x: 1, y: 0 },
-        ];
-        if diagonals {
-            moves.extend_from_slice(&[
-                Position { x: -1, y: -1 },
-                Position { x: -1, y: 1 },
-                Position { x: 1, y: -1 },
-                Position { x: 1, y: 1 },
-            ]);
-        }
+        let mut moves = if diagonals { NEIGHBORS8.to_vec() } else { NEIGHBORS4.to_vec() };
         if zero_move {
             moves.push(Position { x: 0, y: 0 });
         }
@@ -82,12 +210,59 @@ impl Position {
         result
     }
 
+    /// Returns an iterator over the four orthogonal neighbors of this position without allocating.
+    ///
+    pub fn neighbors4(&self) -> impl Iterator<Item = Position> {
+        let position = *self;
+        NEIGHBORS4.into_iter().map(move |mv| position + mv)
+    }
+
+    /// Returns an iterator over all eight neighbors of this position, including diagonals, without allocating.
+    ///
+    pub fn neighbors8(&self) -> impl Iterator<Item = Position> {
+        let position = *self;
+        NEIGHBORS8.into_iter().map(move |mv| position + mv)
+    }
+
+    /// Like [`Position::neighbors4`], but only yields neighbors within the `grid`.
+    ///
+    pub fn neighbors4_within<'a, T>(&self, grid: &'a Grid2d<T>) -> impl Iterator<Item = Position> + 'a {
+        self.neighbors4().filter(move |it| it.is_within(grid))
+    }
+
+    /// Like [`Position::neighbors8`], but only yields neighbors within the `grid`.
+    ///
+    pub fn neighbors8_within<'a, T>(&self, grid: &'a Grid2d<T>) -> impl Iterator<Item = Position> + 'a {
+        self.neighbors8().filter(move |it| it.is_within(grid))
+    }
+
     /// Calculates the manhatten distance between this position and the `other` position.
     ///
     pub fn distance_manhatten(&self, other: &Position) -> i64 {
         (self.x - other.x).abs() + (self.y - other.y).abs()
     }
 
+    /// Calculates the chebyshev distance between this position and the `other` position,
+    /// i.e. the larger difference of both coordinates.
+    ///
+    pub fn distance_chebyshev(&self, other: &Position) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Returns the smallest box containing all `positions` as `(min, max)` corners, both inclusive.
+    /// Returns `None` if there are no positions.
+    ///
+    pub fn bounding_box<'a>(positions: impl IntoIterator<Item = &'a Position>) -> Option<(Position, Position)> {
+        let mut positions = positions.into_iter();
+        let first = *positions.next()?;
+        Some(positions.fold((first, first), |(min, max), it| {
+            (
+                Position { x: min.x.min(it.x), y: min.y.min(it.y) },
+                Position { x: max.x.max(it.x), y: max.y.max(it.y) },
+            )
+        }))
+    }
+
     /// Returns whether this position is within a safe range.
     /// A safe range fulfils these conditions:
     /// * 0 ≤ x < width
@@ -123,6 +298,22 @@ impl Add for Position {
     }
 }
 
+impl Add<Direction> for Position {
+    type Output = Position;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.to_position()
+    }
+}
+
+impl Add<Direction> for &Position {
+    type Output = Position;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        *self + rhs.to_position()
+    }
+}
+
 impl Sub for &Position {
     type Output = Position;
 
@@ -154,3 +345,45 @@ impl Mul<i64> for &Position {
         Position { x: self.x * rhs, y: self.y * rhs }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction_turns() {
+        for direction in Direction::all() {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().to_position(), direction.to_position() * -1);
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+    }
+
+    #[test]
+    fn direction_parse() {
+        assert_eq!("U".parse::<Direction>().unwrap(), Direction::North);
+        assert_eq!("W".parse::<Direction>().unwrap(), Direction::West);
+        assert_eq!(Direction::try_from('v').unwrap(), Direction::South);
+        assert_eq!(Direction::try_from('→').unwrap(), Direction::East);
+        assert!("X".parse::<Direction>().is_err());
+        assert!("UU".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn distances_and_bounds() {
+        let a = Position::at(1, -2);
+        let b = Position::at(-3, 4);
+        assert_eq!(a.distance_chebyshev(&b), 6);
+        assert_eq!(Position::bounding_box(&[a, b]), Some((Position::at(-3, -2), Position::at(1, 4))));
+        assert_eq!(Position::from(PosN::from(a)), a);
+    }
+
+    #[test]
+    fn direction_moves_position() {
+        assert_eq!(Position::at(2, 3) + Direction::North, Position::at(2, 2));
+        assert_eq!(Position::from(Direction::West), WEST);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::util::posn::PosN;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Position3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+/// Moves to the six orthogonal neighbors in a 3-dimensional grid.
+pub const NEIGHBORS6: [Position3; 6] = [
+    Position3 { x: -1, y: 0, z: 0 },
+    Position3 { x: 1, y: 0, z: 0 },
+    Position3 { x: 0, y: -1, z: 0 },
+    Position3 { x: 0, y: 1, z: 0 },
+    Position3 { x: 0, y: 0, z: -1 },
+    Position3 { x: 0, y: 0, z: 1 },
+];
+
+impl From<(i64, i64, i64)> for Position3 {
+    fn from(value: (i64, i64, i64)) -> Self {
+        Self { x: value.0, y: value.1, z: value.2 }
+    }
+}
+
+impl From<PosN<3>> for Position3 {
+    fn from(value: PosN<3>) -> Self {
+        let [x, y, z] = value.0;
+        Self { x, y, z }
+    }
+}
+
+impl From<Position3> for PosN<3> {
+    fn from(value: Position3) -> Self {
+        PosN([value.x, value.y, value.z])
+    }
+}
+
+impl Position3 {
+    pub fn at(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Returns an iterator over the six orthogonal neighbors of this position without allocating.
+    ///
+    pub fn neighbors6(&self) -> impl Iterator<Item = Position3> {
+        let position = *self;
+        NEIGHBORS6.into_iter().map(move |mv| position + mv)
+    }
+
+    /// Returns an iterator over all 26 neighbors of this position, including diagonals, without allocating.
+    ///
+    pub fn neighbors26(&self) -> impl Iterator<Item = Position3> {
+        PosN::from(*self).neighbors(true).map(Position3::from)
+    }
+
+    /// Calculates the manhatten distance between this position and the `other` position.
+    ///
+    pub fn distance_manhatten(&self, other: &Position3) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    /// Calculates the chebyshev distance between this position and the `other` position,
+    /// i.e. the largest difference of any coordinate.
+    ///
+    pub fn distance_chebyshev(&self, other: &Position3) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs()).max((self.z - other.z).abs())
+    }
+
+    /// Returns whether each coordinate lies within the inclusive range of `min` and `max`.
+    ///
+    pub fn is_within_box(&self, min: &Position3, max: &Position3) -> bool {
+        (min.x..=max.x).contains(&self.x) && (min.y..=max.y).contains(&self.y) && (min.z..=max.z).contains(&self.z)
+    }
+
+    /// Returns the smallest box containing all `positions` as `(min, max)` corners, both inclusive.
+    /// Returns `None` if there are no positions.
+    ///
+    pub fn bounding_box<'a>(positions: impl IntoIterator<Item = &'a Position3>) -> Option<(Position3, Position3)> {
+        let mut positions = positions.into_iter();
+        let first = *positions.next()?;
+        Some(positions.fold((first, first), |(min, max), it| {
+            (
+                Position3 { x: min.x.min(it.x), y: min.y.min(it.y), z: min.z.min(it.z) },
+                Position3 { x: max.x.max(it.x), y: max.y.max(it.y), z: max.z.max(it.z) },
+            )
+        }))
+    }
+}
+
+impl Add for &Position3 {
+    type Output = Position3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Position3 { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl Add for Position3 {
+    type Output = Position3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Position3 { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl Sub for &Position3 {
+    type Output = Position3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Position3 { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+impl Sub for Position3 {
+    type Output = Position3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Position3 { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+impl Mul<i64> for Position3 {
+    type Output = Position3;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Position3 { x: self.x * rhs, y: self.y * rhs, z: self.z * rhs }
+    }
+}
+
+impl Mul<i64> for &Position3 {
+    type Output = Position3;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Position3 { x: self.x * rhs, y: self.y * rhs, z: self.z * rhs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors() {
+        let center = Position3::at(1, 2, 3);
+        assert_eq!(center.neighbors6().count(), 6);
+        assert_eq!(center.neighbors26().count(), 26);
+        assert!(center.neighbors6().all(|it| it.distance_manhatten(&center) == 1));
+        assert!(center.neighbors26().all(|it| it.distance_chebyshev(&center) == 1));
+    }
+
+    #[test]
+    fn arithmetic_and_bounds() {
+        let a = Position3::at(1, -2, 3);
+        let b = Position3::at(-1, 4, 0);
+        assert_eq!(a + b, Position3::at(0, 2, 3));
+        assert_eq!(a - b, Position3::at(2, -6, 3));
+        assert_eq!(a * 2, Position3::at(2, -4, 6));
+        assert_eq!(a.distance_manhatten(&b), 11);
+        assert_eq!(a.distance_chebyshev(&b), 6);
+        let (min, max) = Position3::bounding_box(&[a, b]).unwrap();
+        assert_eq!((min, max), (Position3::at(-1, -2, 0), Position3::at(1, 4, 3)));
+        assert!(Position3::at(0, 0, 0).is_within_box(&min, &max));
+        assert!(!Position3::at(0, 5, 0).is_within_box(&min, &max));
+    }
+}
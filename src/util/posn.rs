@@ -0,0 +1,150 @@
+use std::ops::{Add, Mul, Sub};
+
+/// A position in an `N`-dimensional grid.
+/// For two and three dimensions, [`crate::util::position::Position`] and
+/// [`crate::util::position3::Position3`] provide named coordinates and convert from and into this type.
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PosN<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> From<[i64; N]> for PosN<N> {
+    fn from(value: [i64; N]) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: usize> PosN<N> {
+    pub const fn zero() -> Self {
+        Self([0; N])
+    }
+
+    /// Returns an iterator over the neighbors of this position without allocating.
+    /// Without `diagonals` the `2 * N` orthogonal neighbors are returned,
+    /// otherwise all `3^N - 1` positions which differ by at most 1 in each coordinate.
+    ///
+    pub fn neighbors(&self, diagonals: bool) -> impl Iterator<Item = PosN<N>> {
+        let position = *self;
+        let count = if diagonals { 3usize.pow(N as u32) } else { 2 * N };
+        (0..count).filter_map(move |index| {
+            let mut offset = [0; N];
+            if diagonals {
+                let mut rest = index;
+                for value in offset.iter_mut() {
+                    *value = (rest % 3) as i64 - 1;
+                    rest /= 3;
+                }
+                if offset.iter().all(|&it| it == 0) {
+                    return None;
+                }
+            } else {
+                offset[index / 2] = if index % 2 == 0 { -1 } else { 1 };
+            }
+            Some(position + PosN(offset))
+        })
+    }
+
+    /// Calculates the manhatten distance between this position and the `other` position.
+    ///
+    pub fn distance_manhatten(&self, other: &PosN<N>) -> i64 {
+        self.0.iter().zip(other.0.iter()).map(|(a, b)| (a - b).abs()).sum()
+    }
+
+    /// Calculates the chebyshev distance between this position and the `other` position,
+    /// i.e. the largest difference of any coordinate.
+    ///
+    pub fn distance_chebyshev(&self, other: &PosN<N>) -> i64 {
+        self.0.iter().zip(other.0.iter()).map(|(a, b)| (a - b).abs()).max().unwrap_or(0)
+    }
+
+    /// Returns whether each coordinate lies within the inclusive range of `min` and `max`.
+    ///
+    pub fn is_within_box(&self, min: &PosN<N>, max: &PosN<N>) -> bool {
+        (0..N).all(|i| min.0[i] <= self.0[i] && self.0[i] <= max.0[i])
+    }
+
+    /// Returns the smallest box containing all `positions` as `(min, max)` corners, both inclusive.
+    /// Returns `None` if there are no positions.
+    ///
+    pub fn bounding_box<'a>(positions: impl IntoIterator<Item = &'a PosN<N>>) -> Option<(PosN<N>, PosN<N>)> {
+        let mut positions = positions.into_iter();
+        let first = *positions.next()?;
+        Some(positions.fold((first, first), |(mut min, mut max), it| {
+            for i in 0..N {
+                min.0[i] = min.0[i].min(it.0[i]);
+                max.0[i] = max.0[i].max(it.0[i]);
+            }
+            (min, max)
+        }))
+    }
+}
+
+impl<const N: usize> Add for PosN<N> {
+    type Output = PosN<N>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        PosN(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl<const N: usize> Add for &PosN<N> {
+    type Output = PosN<N>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl<const N: usize> Sub for PosN<N> {
+    type Output = PosN<N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        PosN(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl<const N: usize> Sub for &PosN<N> {
+    type Output = PosN<N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl<const N: usize> Mul<i64> for PosN<N> {
+    type Output = PosN<N>;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        PosN(self.0.map(|it| it * rhs))
+    }
+}
+
+impl<const N: usize> Mul<i64> for &PosN<N> {
+    type Output = PosN<N>;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        *self * rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbor_counts() {
+        let origin = PosN::<4>::zero();
+        assert_eq!(origin.neighbors(false).count(), 8);
+        assert_eq!(origin.neighbors(true).count(), 80);
+        assert!(origin.neighbors(false).all(|it| it.distance_manhatten(&origin) == 1));
+        assert!(origin.neighbors(true).all(|it| it.distance_chebyshev(&origin) == 1));
+    }
+
+    #[test]
+    fn bounding_box() {
+        let positions = [PosN([1, -2]), PosN([-3, 4]), PosN([0, 0])];
+        let (min, max) = PosN::bounding_box(&positions).unwrap();
+        assert_eq!((min, max), (PosN([-3, -2]), PosN([1, 4])));
+        assert!(positions.iter().all(|it| it.is_within_box(&min, &max)));
+        assert!(PosN::<2>::bounding_box([]).is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Arithmetic on sets of half-open integer ranges.
+//! A [`RangeSet`] is kept normalized: its ranges are sorted, non-empty, and neither overlap nor touch each other.
+
+use std::fmt::Debug;
+use std::ops::{Add, Range, Sub};
+
+/// The integer types a [`RangeSet`] can hold.
+///
+pub trait RangeValue: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + From<u8> {}
+
+impl<T: Copy + Ord + Debug + Add<Output = T> + Sub<Output = T> + From<u8>> RangeValue for T {}
+
+/// Moves `value` by the distance between `from` and `to`, i.e. `value - from + to`,
+/// without leaving the value range of unsigned types in between.
+///
+fn translate<T: RangeValue>(value: T, from: T, to: T) -> T {
+    if to >= from { value + (to - from) } else { value - (from - to) }
+}
+
+/// A normalized set of half-open ranges.
+///
+/// ```
+/// # use advent_of_code::util::ranges::RangeSet;
+/// let set = RangeSet::from_iter([5..8, 1..3, 2..4, 8..10]);
+/// assert_eq!(set.ranges(), &[1..4, 5..10]);
+/// assert_eq!(set.difference(&RangeSet::from(3..6)).ranges(), &[1..3, 6..10]);
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: RangeValue> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RangeValue> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Sorts the ranges, drops empty ones and coalesces overlapping or adjacent ones.
+    ///
+    fn normalized(mut ranges: Vec<Range<T>>) -> Self {
+        ranges.retain(|it| it.start < it.end);
+        ranges.sort_by_key(|it| it.start);
+
+        let mut result: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match result.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => result.push(range),
+            }
+        }
+        Self { ranges: result }
+    }
+
+    /// Returns the disjoint ranges of this set in ascending order.
+    ///
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Range<T>> {
+        self.ranges.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let index = self.ranges.partition_point(|it| it.end <= *value);
+        self.ranges.get(index).is_some_and(|it| it.contains(value))
+    }
+
+    /// Returns the smallest value in the set.
+    ///
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|it| it.start)
+    }
+
+    /// Returns the largest value in the set.
+    ///
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|it| it.end - T::from(1))
+    }
+
+    /// Returns the number of values in the set.
+    ///
+    pub fn len(&self) -> T {
+        self.ranges.iter().fold(T::from(0), |acc, it| acc + (it.end - it.start))
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        *self = Self::normalized(ranges);
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        Self::normalized(self.ranges.iter().chain(other.ranges.iter()).cloned().collect())
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                result.push(start..end);
+            }
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        Self { ranges: result }
+    }
+
+    /// Returns the values of this set which are not contained in `other`.
+    ///
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                if start < other.ranges[k].start {
+                    result.push(start..other.ranges[k].start);
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+            if start < range.end {
+                result.push(start..range.end);
+            }
+        }
+        Self { ranges: result }
+    }
+
+    /// Moves every value by the distance between `from` and `to`, i.e. maps `value` to `value - from + to`.
+    ///
+    pub fn shift(&self, from: T, to: T) -> RangeSet<T> {
+        let ranges = self.ranges.iter()
+            .map(|it| translate(it.start, from, to)..translate(it.end, from, to))
+            .collect();
+        Self { ranges }
+    }
+}
+
+impl<T: RangeValue> From<Range<T>> for RangeSet<T> {
+    fn from(value: Range<T>) -> Self {
+        Self::normalized(vec![value])
+    }
+}
+
+impl<T: RangeValue> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().collect())
+    }
+}
+
+/// A piecewise-linear lookup table which moves every source range to its destination
+/// and maps values outside of all source ranges onto themselves.
+/// If source ranges overlap, the first one added wins.
+///
+/// ```
+/// # use advent_of_code::util::ranges::{PiecewiseMap, RangeSet};
+/// let mut map = PiecewiseMap::new();
+/// map.add(98..100, 50);
+/// map.add(50..98, 52);
+/// assert_eq!(map.map(99), 51);
+/// assert_eq!(map.map(10), 10);
+/// assert_eq!(map.map_set(&RangeSet::from(96..101)).ranges(), &[50..52, 98..101]);
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct PiecewiseMap<T> {
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T: RangeValue> PiecewiseMap<T> {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Maps the values of `source` to a range of the same length starting at `destination`.
+    ///
+    pub fn add(&mut self, source: Range<T>, destination: T) {
+        self.entries.push((source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        self.entries.iter()
+            .find(|(source, _)| source.contains(&value))
+            .map_or(value, |(source, destination)| translate(value, source.start, *destination))
+    }
+
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut remaining = set.clone();
+        let mut result = RangeSet::new();
+        for (source, destination) in &self.entries {
+            let source_set = RangeSet::from(source.clone());
+            let mapped = remaining.intersection(&source_set).shift(source.start, *destination);
+            result = result.union(&mapped);
+            remaining = remaining.difference(&source_set);
+        }
+        result.union(&remaining)
+    }
+}
+
+impl<T: RangeValue> FromIterator<(Range<T>, T)> for PiecewiseMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Range<T>, T)>>(iter: I) -> Self {
+        Self { entries: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn values(set: &RangeSet<u32>) -> BTreeSet<u32> {
+        set.iter().flat_map(|it| it.clone()).collect()
+    }
+
+    /// Generates small random range sets with a xorshift generator, so the tests stay deterministic.
+    fn random_sets(count: usize) -> Vec<RangeSet<u32>> {
+        let mut state = 0x2545_f491_u32;
+        let mut next = move |bound: u32| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % bound
+        };
+        (0..count)
+            .map(|_| (0..next(5)).map(|_| {
+                let start = next(40);
+                start..start + next(10)
+            }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn normalizes() {
+        let set = RangeSet::from_iter([7..7, 4..6, 0..2, 1..3, 3..4]);
+        assert_eq!(set, RangeSet::from(0..6));
+        assert!(RangeSet::from(5..5).is_empty());
+        assert!(set.contains(&5) && !set.contains(&6));
+        assert_eq!((set.min(), set.max(), set.len()), (Some(0), Some(5), 6));
+    }
+
+    #[test]
+    fn set_operations_match_brute_force() {
+        let sets = random_sets(40);
+        for a in &sets {
+            for b in &sets {
+                let (va, vb) = (values(a), values(b));
+                assert_eq!(values(&a.union(b)), &va | &vb);
+                assert_eq!(values(&a.intersection(b)), &va & &vb);
+                assert_eq!(values(&a.difference(b)), &va - &vb);
+                assert_eq!(a.union(b), RangeSet::from_iter(a.union(b).iter().cloned()), "result must be normalized");
+                assert_eq!(a.difference(b).union(&a.intersection(b)), *a);
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_unsigned() {
+        let set = RangeSet::from_iter([10_u64..12, 20..21]);
+        assert_eq!(set.shift(10, 0).ranges(), &[0..2, 10..11]);
+        assert_eq!(set.shift(0, 5).ranges(), &[15..17, 25..26]);
+    }
+
+    #[test]
+    fn map_set_matches_map() {
+        let map: PiecewiseMap<u32> = [(5..15, 30), (10..20, 0), (40..45, 12)].into_iter().collect();
+        for set in random_sets(40) {
+            let expected: BTreeSet<u32> = values(&set).into_iter().map(|it| map.map(it)).collect();
+            assert_eq!(values(&map.map_set(&set)), expected);
+        }
+    }
+}
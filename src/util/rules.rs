@@ -0,0 +1,291 @@
+//! An interpreter for programs of named workflows with conditional rules, e.g. `px{a<2006:qkq,m>2090:A,rhg}`.
+//!
+//! A workflow sends a record to the target of its first rule whose condition holds,
+//! until a record is accepted by `A` or rejected by `R`.
+//! Besides single records, whole regions of attribute ranges can be evaluated at once,
+//! which splits them into the parts that are accepted.
+
+use crate::template::SolutionError;
+use crate::util::parse::{map_lines, parse_field};
+use crate::util::ranges::RangeSet;
+use std::collections::{BTreeMap, HashMap};
+
+/// The largest value of an attribute. Regions consist of half-open ranges, so `u64::MAX` itself is not a valid value.
+///
+pub const MAX_VALUE: u64 = u64::MAX - 1;
+
+/// The values of the attributes of a record, e.g. `{x=787,m=2655,a=1222,s=2876}`, each at most [`MAX_VALUE`].
+///
+pub type Record<'a> = BTreeMap<&'a str, u64>;
+
+/// A hyper-rectangle of records, given by the set of values of each attribute.
+///
+pub type Region<'a> = BTreeMap<&'a str, RangeSet<u64>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    Greater,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition<'a> {
+    pub attribute: &'a str,
+    pub comparison: Comparison,
+    pub value: u64,
+}
+
+impl<'a> Condition<'a> {
+    fn parse(text: &'a str) -> Result<Condition<'a>, SolutionError> {
+        let index = text.find(['<', '>'])
+            .ok_or_else(|| SolutionError::near(text, "expected a condition like a<2006"))?;
+        let attribute = &text[..index];
+        if attribute.is_empty() {
+            return Err(SolutionError::near(text, "expected an attribute before the comparison"));
+        }
+        let comparison = if &text[index..=index] == "<" { Comparison::Less } else { Comparison::Greater };
+        let value = parse_field(&text[index + 1..])?;
+        Ok(Condition { attribute, comparison, value })
+    }
+
+    pub fn matches(&self, value: u64) -> bool {
+        match self.comparison {
+            Comparison::Less => value < self.value,
+            Comparison::Greater => value > self.value,
+        }
+    }
+
+    /// Returns all values of the attribute up to [`MAX_VALUE`] for which the condition holds.
+    ///
+    pub fn matching(&self) -> RangeSet<u64> {
+        match self.comparison {
+            Comparison::Less => RangeSet::from(0..self.value),
+            Comparison::Greater => RangeSet::from(self.value.saturating_add(1)..u64::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target<'a> {
+    Accept,
+    Reject,
+    Workflow(&'a str),
+}
+
+impl<'a> From<&'a str> for Target<'a> {
+    fn from(value: &'a str) -> Self {
+        match value {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            _ => Target::Workflow(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule<'a> {
+    /// The rule always applies if there is no condition.
+    pub condition: Option<Condition<'a>>,
+    pub target: Target<'a>,
+}
+
+impl<'a> Rule<'a> {
+    fn parse(text: &'a str) -> Result<Rule<'a>, SolutionError> {
+        match text.split_once(':') {
+            None => Ok(Rule { condition: None, target: Target::from(text) }),
+            Some((condition, target)) => Ok(Rule { condition: Some(Condition::parse(condition)?), target: Target::from(target) }),
+        }
+    }
+}
+
+/// The workflows of a program by their names.
+///
+/// ```
+/// # use advent_of_code::util::rules::{parse_record, Program};
+/// let program = Program::parse("in{x>10:A,m<5:big,R}\nbig{a<3:R,A}").unwrap();
+/// assert!(program.evaluate("in", &parse_record("{x=1,m=2,a=3}").unwrap()).unwrap());
+///
+/// let region = [("x", 1..21), ("m", 1..11), ("a", 1..11)].into_iter().map(|(k, v)| (k, v.into())).collect();
+/// assert_eq!(program.count_accepted("in", region).unwrap(), 10 * 10 * 10 + 10 * 4 * 8);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Program<'a> {
+    workflows: HashMap<&'a str, Vec<Rule<'a>>>,
+}
+
+impl<'a> Program<'a> {
+    /// Parses one workflow per line, e.g. `px{a<2006:qkq,m>2090:A,rhg}`.
+    ///
+    pub fn parse(input: &'a str) -> Result<Program<'a>, SolutionError> {
+        let workflows = map_lines(input, |line| {
+            let line = line.trim();
+            let (name, rules) = line.split_once('{')
+                .ok_or_else(|| SolutionError::near(line, "expected a workflow like px{a<2006:qkq,rfg}"))?;
+            let rules = rules.strip_suffix('}')
+                .ok_or_else(|| SolutionError::near(line, "expected } at the end of the workflow"))?;
+            let rules = rules.split(',').map(Rule::parse).collect::<Result<_, _>>()?;
+            Ok((name, rules))
+        })?;
+        Ok(Program { workflows: workflows.into_iter().collect() })
+    }
+
+    pub fn workflow(&self, name: &str) -> Result<&[Rule<'a>], SolutionError> {
+        self.workflows.get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| SolutionError::new(format!("unknown workflow {name}")))
+    }
+
+    /// Runs `record` through the program starting at the workflow `start` and returns whether it is accepted.
+    /// Fails on unknown workflows or attributes, and on records which loop forever.
+    ///
+    pub fn evaluate(&self, start: &str, record: &Record) -> Result<bool, SolutionError> {
+        let mut current = start;
+        // without a loop, every workflow is visited at most once
+        for _ in 0..=self.workflows.len() {
+            let mut target = None;
+            for rule in self.workflow(current)? {
+                let matches = match &rule.condition {
+                    None => true,
+                    Some(condition) => condition.matches(attribute(record, condition.attribute)?),
+                };
+                if matches {
+                    target = Some(rule.target);
+                    break;
+                }
+            }
+            match target.ok_or_else(|| SolutionError::new(format!("no rule of workflow {current} matches")))? {
+                Target::Accept => return Ok(true),
+                Target::Reject => return Ok(false),
+                Target::Workflow(next) => current = next,
+            }
+        }
+        Err(SolutionError::new(format!("the record loops starting at workflow {start}")))
+    }
+
+    /// Splits `region` into the disjoint regions which are accepted when starting at the workflow `start`.
+    ///
+    pub fn accepted(&self, start: &str, region: Region<'a>) -> Result<Vec<Region<'a>>, SolutionError> {
+        let mut result = Vec::new();
+        self.collect_accepted(start, region, 0, &mut result)?;
+        Ok(result)
+    }
+
+    /// Counts the records within `region` which are accepted when starting at the workflow `start`.
+    ///
+    pub fn count_accepted(&self, start: &str, region: Region<'a>) -> Result<u64, SolutionError> {
+        let regions = self.accepted(start, region)?;
+        Ok(regions.iter().map(|it| it.values().map(RangeSet::len).product::<u64>()).sum())
+    }
+
+    fn collect_accepted(&self, workflow: &str, mut region: Region<'a>, depth: usize, result: &mut Vec<Region<'a>>) -> Result<(), SolutionError> {
+        if depth > self.workflows.len() {
+            return Err(SolutionError::new(format!("the program loops at workflow {workflow}")));
+        }
+        for rule in self.workflow(workflow)? {
+            let matched = match &rule.condition {
+                None => std::mem::take(&mut region),
+                Some(condition) => {
+                    let values = region.get_mut(condition.attribute)
+                        .ok_or_else(|| SolutionError::new(format!("the region has no attribute {}", condition.attribute)))?;
+                    let matching = condition.matching();
+                    let matched_values = values.intersection(&matching);
+                    *values = values.difference(&matching);
+                    let mut matched = region.clone();
+                    matched.insert(condition.attribute, matched_values);
+                    matched
+                }
+            };
+            if !is_empty(&matched) {
+                match rule.target {
+                    Target::Accept => result.push(matched),
+                    Target::Reject => {}
+                    Target::Workflow(next) => self.collect_accepted(next, matched, depth + 1, result)?,
+                }
+            }
+            if rule.condition.is_none() || is_empty(&region) {
+                return Ok(());
+            }
+        }
+        Err(SolutionError::new(format!("no rule of workflow {workflow} matches")))
+    }
+}
+
+fn attribute(record: &Record, name: &str) -> Result<u64, SolutionError> {
+    record.get(name).copied().ok_or_else(|| SolutionError::new(format!("the record has no attribute {name}")))
+}
+
+fn is_empty(region: &Region) -> bool {
+    region.is_empty() || region.values().any(RangeSet::is_empty)
+}
+
+/// Parses a record like `{x=787,m=2655,a=1222,s=2876}`.
+///
+pub fn parse_record(line: &str) -> Result<Record<'_>, SolutionError> {
+    let attributes = line.trim().strip_prefix('{')
+        .and_then(|it| it.strip_suffix('}'))
+        .ok_or_else(|| SolutionError::near(line, "expected a record like {x=787,m=2655}"))?;
+    attributes.split(',')
+        .map(|it| {
+            let (name, value) = it.split_once('=')
+                .ok_or_else(|| SolutionError::near(it, "expected an attribute like x=787"))?;
+            let value = parse_field(value)?;
+            if value > MAX_VALUE {
+                return Err(SolutionError::near(it, format!("expected a value of at most {MAX_VALUE}")));
+            }
+            Ok((name.trim(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(ranges: &[(&'static str, std::ops::Range<u64>)]) -> Region<'static> {
+        ranges.iter().map(|(name, range)| (*name, RangeSet::from(range.clone()))).collect()
+    }
+
+    #[test]
+    fn parses_rules() {
+        let program = Program::parse("px{a<2006:qkq,m>2090:A,rhg}").unwrap();
+        let rules = program.workflow("px").unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].condition, Some(Condition { attribute: "a", comparison: Comparison::Less, value: 2006 }));
+        assert_eq!((rules[1].target, rules[2].target), (Target::Accept, Target::Workflow("rhg")));
+        assert!(Program::parse("px{a=5:A,R}").is_err());
+        assert!(Program::parse("px{A").is_err());
+    }
+
+    #[test]
+    fn symbolic_evaluation_matches_records() {
+        let program = Program::parse("in{x<4:lo,y>2:A,R}\nlo{y<2:R,x>1:A,hi}\nhi{y>3:A,R}").unwrap();
+        let accepted = program.accepted("in", region(&[("x", 0..6), ("y", 0..6)])).unwrap();
+        for x in 0..6 {
+            for y in 0..6 {
+                let record = Record::from([("x", x), ("y", y)]);
+                let expected = program.evaluate("in", &record).unwrap();
+                let inside = accepted.iter().filter(|it| it["x"].contains(&x) && it["y"].contains(&y)).count();
+                assert_eq!(inside, expected as usize, "x={x}, y={y}");
+            }
+        }
+        assert_eq!(program.count_accepted("in", region(&[("x", 0..6), ("y", 0..6)])).unwrap(), 18);
+    }
+
+    #[test]
+    fn reports_invalid_programs() {
+        let program = Program::parse("in{x<4:in,R}\nout{z>1:A,R}").unwrap();
+        assert!(program.evaluate("in", &Record::from([("x", 1)])).is_err());
+        assert!(program.count_accepted("in", region(&[("x", 0..6)])).is_err());
+        assert!(program.evaluate("out", &Record::from([("x", 1)])).is_err());
+        assert!(program.evaluate("missing", &Record::new()).is_err());
+        assert_eq!(parse_record("{x=787,m=2655}").unwrap(), Record::from([("x", 787), ("m", 2655)]));
+    }
+
+    #[test]
+    fn greater_covers_values_up_to_the_maximum() {
+        let condition = Condition { attribute: "x", comparison: Comparison::Greater, value: 10 };
+        assert!(condition.matches(MAX_VALUE) && condition.matching().contains(&MAX_VALUE));
+        assert!(parse_record(&format!("{{x={}}}", u64::MAX)).is_err());
+    }
+}
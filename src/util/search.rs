@@ -0,0 +1,341 @@
+use crate::util::grid2d::Grid2d;
+use crate::util::position::Position;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// The result of an exhaustive search.
+/// Holds the distance of every reached state and the predecessor it was reached from,
+/// so paths can be reconstructed afterwards.
+///
+#[derive(Debug)]
+pub struct Search<S> {
+    distances: HashMap<S, u64>,
+    parents: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn new() -> Self {
+        Self { distances: HashMap::new(), parents: HashMap::new() }
+    }
+
+    /// Returns the distance of `state` from the start or `None` if it was not reached.
+    ///
+    pub fn distance(&self, state: &S) -> Option<u64> {
+        self.distances.get(state).copied()
+    }
+
+    /// Returns the distances of all reached states.
+    ///
+    pub fn distances(&self) -> &HashMap<S, u64> {
+        &self.distances
+    }
+
+    /// Returns whether `state` was reached by the search.
+    ///
+    pub fn contains(&self, state: &S) -> bool {
+        self.distances.contains_key(state)
+    }
+
+    /// Reconstructs the path from the start to `target`, both included.
+    /// Returns `None` if `target` was not reached.
+    ///
+    pub fn path_to(&self, target: &S) -> Option<Vec<S>> {
+        if !self.contains(target) {
+            return None;
+        }
+        Some(build_path(&self.parents, target.clone()))
+    }
+}
+
+fn build_path<S: Clone + Eq + Hash>(parents: &HashMap<S, S>, target: S) -> Vec<S> {
+    let mut path = vec![target];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search over arbitrary states, starting from all `starts` at once.
+/// Every reachable state is visited, the distance counts the number of steps.
+///
+pub fn bfs_by<S, I>(starts: impl IntoIterator<Item = S>, mut successors: impl FnMut(&S) -> I) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let distance = search.distances[&current] + 1;
+        for next in successors(&current) {
+            if search.contains(&next) {
+                continue;
+            }
+            search.distances.insert(next.clone(), distance);
+            search.parents.insert(next.clone(), current.clone());
+            queue.push_back(next);
+        }
+    }
+    search
+}
+
+/// Depth-first search over arbitrary states.
+/// The distance of a state is its depth in the search tree, which is not necessarily the shortest distance.
+///
+pub fn dfs_by<S, I>(start: S, mut successors: impl FnMut(&S) -> I) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new();
+    let mut stack = vec![(start, None::<S>, 0)];
+
+    while let Some((current, parent, distance)) = stack.pop() {
+        if search.contains(&current) {
+            continue;
+        }
+        search.distances.insert(current.clone(), distance);
+        if let Some(parent) = parent {
+            search.parents.insert(current.clone(), parent);
+        }
+        for next in successors(&current) {
+            if !search.contains(&next) {
+                stack.push((next, Some(current.clone()), distance + 1));
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm over arbitrary states, starting from all `starts` at once.
+/// Every reachable state is visited.
+///
+pub fn dijkstra_all_by<S, I>(starts: impl IntoIterator<Item = S>, mut successors: impl FnMut(&S) -> I) -> Search<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut search = Search::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        search.distances.insert(start.clone(), 0);
+        queue.push(Reverse((0, start)));
+    }
+
+    while let Some(Reverse((cost, current))) = queue.pop() {
+        if cost > search.distances[&current] {
+            continue;
+        }
+        for (next, step_cost) in successors(&current) {
+            let next_cost = cost + step_cost;
+            if search.distance(&next).is_some_and(|it| it <= next_cost) {
+                continue;
+            }
+            search.distances.insert(next.clone(), next_cost);
+            search.parents.insert(next.clone(), current.clone());
+            queue.push(Reverse((next_cost, next)));
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm over arbitrary states, starting from all `starts` at once.
+/// Returns the path to the first state fulfilling `success` together with its cost.
+///
+pub fn dijkstra_by<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    success: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, u64)>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    astar_by(starts, successors, |_| 0, success)
+}
+
+/// A* search over arbitrary states, starting from all `starts` at once.
+/// The `heuristic` must never overestimate the remaining cost to reach a successful state.
+/// Returns the path to the first state fulfilling `success` together with its cost.
+///
+pub fn astar_by<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut success: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, u64)>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        costs.insert(start.clone(), 0);
+        queue.push(Reverse((heuristic(&start), 0, start)));
+    }
+
+    while let Some(Reverse((_, cost, current))) = queue.pop() {
+        if cost > costs[&current] {
+            continue;
+        }
+        if success(&current) {
+            return Some((build_path(&parents, current), cost));
+        }
+        for (next, step_cost) in successors(&current) {
+            let next_cost = cost + step_cost;
+            if costs.get(&next).is_some_and(|&it| it <= next_cost) {
+                continue;
+            }
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), current.clone());
+            queue.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+        }
+    }
+    None
+}
+
+/// Breadth-first search on a grid moving in the four cardinal directions.
+/// A move from one position to a neighbor is only possible if `passable(from, to)` returns true.
+///
+pub fn bfs<T>(grid: &Grid2d<T>, start: Position, passable: impl Fn(&Position, &Position) -> bool) -> Search<Position> {
+    let passable = &passable;
+    bfs_by([start], |&current| {
+        current.neighbors4_within(grid)
+            .filter(move |next| passable(&current, next))
+    })
+}
+
+/// Depth-first search on a grid moving in the four cardinal directions.
+/// A move from one position to a neighbor is only possible if `passable(from, to)` returns true.
+///
+pub fn dfs<T>(grid: &Grid2d<T>, start: Position, passable: impl Fn(&Position, &Position) -> bool) -> Search<Position> {
+    let passable = &passable;
+    dfs_by(start, |&current| {
+        current.neighbors4_within(grid)
+            .filter(move |next| passable(&current, next))
+    })
+}
+
+/// Dijkstra's algorithm on a grid moving in the four cardinal directions.
+/// `cost(from, to)` returns the cost of a move or `None` if the move is not possible.
+/// Returns the cheapest path from `start` to `goal` together with its cost.
+///
+pub fn dijkstra<T>(
+    grid: &Grid2d<T>,
+    start: Position,
+    goal: Position,
+    cost: impl Fn(&Position, &Position) -> Option<u64>,
+) -> Option<(Vec<Position>, u64)> {
+    dijkstra_by([start], |&current| grid_successors(grid, current, &cost), |it| *it == goal)
+}
+
+/// A* search on a grid moving in the four cardinal directions, using the manhatten distance as heuristic.
+/// `cost(from, to)` returns the cost of a move or `None` if the move is not possible.
+/// Each move must cost at least 1 for the heuristic to be admissible.
+/// Returns the cheapest path from `start` to `goal` together with its cost.
+///
+pub fn astar<T>(
+    grid: &Grid2d<T>,
+    start: Position,
+    goal: Position,
+    cost: impl Fn(&Position, &Position) -> Option<u64>,
+) -> Option<(Vec<Position>, u64)> {
+    astar_by(
+        [start],
+        |&current| grid_successors(grid, current, &cost),
+        |it| it.distance_manhatten(&goal) as u64,
+        |it| *it == goal,
+    )
+}
+
+fn grid_successors<'a, T>(
+    grid: &'a Grid2d<T>,
+    current: Position,
+    cost: &'a impl Fn(&Position, &Position) -> Option<u64>,
+) -> impl Iterator<Item = (Position, u64)> + 'a {
+    current.neighbors4_within(grid)
+        .filter_map(move |next| cost(&current, &next).map(|it| (next, it)))
+}
+
+/// Returns all positions connected to `start` through positions for which `passable` returns true.
+/// The result is empty if `start` itself is outside the grid or not passable.
+///
+pub fn flood_fill<T>(grid: &Grid2d<T>, start: Position, passable: impl Fn(&Position) -> bool) -> HashSet<Position> {
+    if !start.is_within(grid) || !passable(&start) {
+        return HashSet::new();
+    }
+    bfs(grid, start, |_, next| passable(next))
+        .distances
+        .into_keys()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+..#.
+..#.
+....
+#.#.";
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let grid = Grid2d::of_lines(MAZE);
+        let search = bfs(&grid, Position::at(0, 0), |_, to| grid.get(to) != '#');
+        assert_eq!(search.distance(&Position::at(3, 0)), Some(7));
+        assert_eq!(search.distance(&Position::at(0, 3)), None);
+        let path = search.path_to(&Position::at(3, 0)).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.first(), Some(&Position::at(0, 0)));
+        assert_eq!(path.last(), Some(&Position::at(3, 0)));
+    }
+
+    #[test]
+    fn dfs_reaches_all_connected() {
+        let grid = Grid2d::of_lines(MAZE);
+        let search = dfs(&grid, Position::at(0, 0), |_, to| grid.get(to) != '#');
+        assert_eq!(search.distances().len(), 12);
+        let path = search.path_to(&Position::at(3, 3)).unwrap();
+        assert!(path.windows(2).all(|it| it[0].distance_manhatten(&it[1]) == 1));
+    }
+
+    #[test]
+    fn dijkstra_and_astar_agree() {
+        let grid = Grid2d::of_lines("1163\n1381\n2136");
+        let cost = |_: &Position, to: &Position| grid.get(to).to_digit(10).map(u64::from);
+        let goal = Position::at(3, 2);
+        let (path, cost_dijkstra) = dijkstra(&grid, Position::at(0, 0), goal, cost).unwrap();
+        let (_, cost_astar) = astar(&grid, Position::at(0, 0), goal, cost).unwrap();
+        assert_eq!(cost_dijkstra, 13);
+        assert_eq!(cost_astar, cost_dijkstra);
+        assert_eq!(path.last(), Some(&goal));
+    }
+
+    #[test]
+    fn dijkstra_all_visits_every_state() {
+        let search = dijkstra_all_by([0u32], |&it| if it < 5 { vec![(it + 1, 2), (it + 2, 5)] } else { vec![] });
+        assert_eq!(search.distance(&4), Some(8));
+        assert_eq!(search.distance(&6), Some(13));
+        assert_eq!(search.path_to(&4), Some(vec![0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn flood_fill_stays_in_region() {
+        let grid = Grid2d::of_lines(MAZE);
+        assert_eq!(flood_fill(&grid, Position::at(0, 0), |it| grid.get(it) != '#').len(), 12);
+        assert!(flood_fill(&grid, Position::at(2, 0), |it| grid.get(it) != '#').is_empty());
+        assert!(flood_fill(&grid, Position::at(-1, 0), |_| true).is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+//! Extrapolation of integer sequences which follow a polynomial, e.g. values sampled from quadratic growth.
+
+use crate::util::math::Rational;
+
+/// Builds the table of finite differences of `values`, starting with the values themselves.
+/// The table ends with the first row whose values are all equal, or with a single value if no such row exists.
+///
+/// ```
+/// # use advent_of_code::util::sequence::difference_table;
+/// assert_eq!(difference_table(&[1, 3, 6, 10]), vec![vec![1, 3, 6, 10], vec![2, 3, 4], vec![1, 1]]);
+/// ```
+///
+pub fn difference_table(values: &[i64]) -> Vec<Vec<i64>> {
+    let mut table = vec![values.to_vec()];
+    loop {
+        let current = table.last().unwrap();
+        if current.windows(2).all(|it| it[0] == it[1]) {
+            return table;
+        }
+        let next = current.windows(2).map(|it| it[1] - it[0]).collect();
+        table.push(next);
+    }
+}
+
+/// Returns the degree of the polynomial generating `values`,
+/// or `None` if there are too few values to confirm it, i.e. less than `degree + 2`.
+///
+pub fn degree(values: &[i64]) -> Option<usize> {
+    let table = difference_table(values);
+    (table.last().unwrap().len() >= 2).then_some(table.len() - 1)
+}
+
+/// Predicts the value following `values` from their finite differences.
+///
+/// # Panics
+/// Panics if `values` is empty.
+///
+pub fn extrapolate_next(values: &[i64]) -> i64 {
+    assert!(!values.is_empty(), "cannot extrapolate an empty sequence");
+    difference_table(values).iter()
+        .map(|it| it.last().unwrap())
+        .sum()
+}
+
+/// Predicts the value preceding `values` from their finite differences.
+///
+/// # Panics
+/// Panics if `values` is empty.
+///
+pub fn extrapolate_previous(values: &[i64]) -> i64 {
+    assert!(!values.is_empty(), "cannot extrapolate an empty sequence");
+    difference_table(values).iter()
+        .rev()
+        .fold(0, |below, it| it[0] - below)
+}
+
+/// Evaluates the polynomial of lowest degree passing through all `points` at `x` using Lagrange interpolation.
+/// The calculation is exact, so the result can be checked with [`Rational::to_integer`].
+///
+/// ```
+/// # use advent_of_code::util::sequence::interpolate;
+/// // y = x² + 1
+/// let points = [(0, 1), (2, 5), (5, 26)];
+/// assert_eq!(interpolate(&points, 26501365).to_integer(), Some(26501365_i128.pow(2) + 1));
+/// ```
+///
+/// # Panics
+/// Panics if two points have the same `x`.
+///
+pub fn interpolate(points: &[(i64, i64)], x: i64) -> Rational {
+    points.iter().enumerate()
+        .map(|(i, (xi, yi))| {
+            points.iter().enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Rational::from(*yi), |acc, (_, (xj, _))| {
+                    acc * Rational::new((x - xj) as i128, (xi - xj) as i128)
+                })
+        })
+        .fold(Rational::from(0), |acc, it| acc + it)
+}
+
+/// Calculates the coefficients of the polynomial of lowest degree passing through all `points`,
+/// starting with the constant term.
+///
+/// # Panics
+/// Panics if two points have the same `x`.
+///
+pub fn fit_polynomial(points: &[(i64, i64)]) -> Vec<Rational> {
+    let mut coefficients = vec![Rational::from(0); points.len()];
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        // expand the Lagrange basis polynomial of point i
+        let mut basis = vec![Rational::from(1)];
+        let mut denominator = Rational::from(1);
+        for (_, (xj, _)) in points.iter().enumerate().filter(|(j, _)| *j != i) {
+            let mut next = vec![Rational::from(0); basis.len() + 1];
+            for (k, coefficient) in basis.iter().enumerate() {
+                next[k + 1] = next[k + 1] + *coefficient;
+                next[k] = next[k] - *coefficient * Rational::from(*xj);
+            }
+            basis = next;
+            denominator = denominator * Rational::from(xi - xj);
+        }
+        let factor = Rational::from(*yi) / denominator;
+        for (k, coefficient) in basis.into_iter().enumerate() {
+            coefficients[k] = coefficients[k] + coefficient * factor;
+        }
+    }
+    while coefficients.len() > 1 && coefficients.last() == Some(&Rational::from(0)) {
+        coefficients.pop();
+    }
+    coefficients
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extrapolates_both_directions() {
+        assert_eq!(extrapolate_next(&[0, 3, 6, 9, 12, 15]), 18);
+        assert_eq!(extrapolate_next(&[10, 13, 16, 21, 30, 45]), 68);
+        assert_eq!(extrapolate_previous(&[10, 13, 16, 21, 30, 45]), 5);
+        assert_eq!(extrapolate_next(&[7]), 7);
+        assert_eq!(extrapolate_previous(&[4, 2]), 6);
+    }
+
+    #[test]
+    fn detects_degree() {
+        assert_eq!(degree(&[5, 5, 5]), Some(0));
+        assert_eq!(degree(&[1, 3, 5, 7]), Some(1));
+        assert_eq!(degree(&[1, 4, 9, 16]), Some(2));
+        assert_eq!(degree(&[0, 0, 0]), Some(0));
+        assert_eq!(degree(&[1, 4, 9]), None);
+        assert_eq!(degree(&[1, 2, 4, 8, 16]), None);
+    }
+
+    #[test]
+    fn interpolation_matches_extrapolation() {
+        let values = [10, 13, 16, 21, 30, 45];
+        let points: Vec<_> = values.iter().enumerate().map(|(x, y)| (x as i64, *y)).collect();
+        assert_eq!(interpolate(&points, 6).to_integer(), Some(68));
+        assert_eq!(interpolate(&points, -1).to_integer(), Some(5));
+        assert_eq!(interpolate(&[(0, 0), (2, 1)], 1), Rational::new(1, 2));
+    }
+
+    #[test]
+    fn fits_coefficients() {
+        // y = 3x² - x / 2 + 4
+        let points = [(0, 4), (2, 15), (4, 50)];
+        assert_eq!(fit_polynomial(&points), vec![Rational::from(4), Rational::new(-1, 2), Rational::from(3)]);
+        assert_eq!(fit_polynomial(&[(1, 2), (2, 4), (3, 6)]), vec![Rational::from(0), Rational::from(2)]);
+    }
+}
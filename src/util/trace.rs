@@ -0,0 +1,184 @@
+//! Tracing of intermediate state, enabled with `cargo solve NN --trace`.
+//!
+//! Solutions emit events with [`trace!`](crate::trace) and grid snapshots with [`trace_grid!`](crate::trace_grid).
+//! Both macros expand to nothing unless the `trace` feature is enabled, so benchmarks run by `cargo time`,
+//! which builds without it, contain no trace of them. With the feature, the runner starts tracing for the first run
+//! of every part. Events go to stderr, which the runner does not parse, or with `--trace-log` to `data/trace/NN.log`.
+//!
+//! ```ignore
+//! advent_of_code::trace!("cycle found", start = start, length = length);
+//! advent_of_code::trace_grid!("after tilting north", &grid);
+//! ```
+
+use crate::template::Day;
+use std::fmt::{Debug, Display};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use std::{env, fs};
+
+/// Where the events of a traced run go.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    Stderr,
+    /// The per-day log file in `data/trace/`, which is truncated by the first part traced in a process.
+    Log,
+}
+
+struct Tracer {
+    day: Day,
+    part: u8,
+    start: Instant,
+    output: Box<dyn Write + Send>,
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static TRACER: Mutex<Option<Tracer>> = Mutex::new(None);
+static LOG_CREATED: AtomicBool = AtomicBool::new(false);
+
+/// Returns whether events are currently written.
+///
+pub fn enabled() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Returns the path of the log file of a day.
+///
+pub fn log_path(day: Day) -> PathBuf {
+    env::current_dir().unwrap().join("data").join("trace").join(format!("{day}.log"))
+}
+
+fn open_log(path: &Path) -> io::Result<File> {
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    let append = LOG_CREATED.swap(true, Ordering::Relaxed);
+    OpenOptions::new().create(true).write(true).append(append).truncate(!append).open(path)
+}
+
+/// Starts writing the events of a part to `output`. Falls back to stderr if the log file cannot be opened.
+///
+pub fn start(day: Day, part: u8, output: Output) {
+    let output: Box<dyn Write + Send> = match output {
+        Output::Stderr => Box::new(io::stderr()),
+        Output::Log => match open_log(&log_path(day)) {
+            Ok(file) => Box::new(io::BufWriter::new(file)),
+            Err(error) => {
+                eprintln!("could not open the trace log, tracing to stderr: {error}");
+                Box::new(io::stderr())
+            }
+        },
+    };
+    *TRACER.lock().unwrap() = Some(Tracer { day, part, start: Instant::now(), output });
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+/// Stops tracing and flushes the output.
+///
+pub fn stop() {
+    ACTIVE.store(false, Ordering::Relaxed);
+    if let Some(mut tracer) = TRACER.lock().unwrap().take() {
+        let _ = tracer.output.flush();
+    }
+}
+
+fn with_tracer(write: impl FnOnce(&mut dyn Write, String) -> io::Result<()>) {
+    if !enabled() {
+        return;
+    }
+    if let Some(tracer) = TRACER.lock().unwrap().as_mut() {
+        let prefix = format!("[day {} part {} +{:.1?}]", tracer.day, tracer.part, tracer.start.elapsed());
+        // a failing trace must not fail the solution
+        let _ = write(&mut tracer.output, prefix);
+    }
+}
+
+/// Returns the file name of the `file!()` of a call site.
+///
+fn source(file: &str, line: u32) -> String {
+    let name = Path::new(file).file_name().map_or(file.into(), |it| it.to_string_lossy());
+    format!("{name}:{line}")
+}
+
+fn write_event(output: &mut dyn Write, prefix: &str, source: &str, event: &str, fields: &[(&str, &dyn Debug)]) -> io::Result<()> {
+    write!(output, "{prefix} {source} {event}")?;
+    for (key, value) in fields {
+        write!(output, " {key}={value:?}")?;
+    }
+    writeln!(output)
+}
+
+fn write_grid(output: &mut dyn Write, prefix: &str, source: &str, label: &str, grid: &dyn Display) -> io::Result<()> {
+    writeln!(output, "{prefix} {source} {label}")?;
+    for line in grid.to_string().lines() {
+        writeln!(output, "  {line}")?;
+    }
+    Ok(())
+}
+
+/// Writes an event with its fields, called by [`trace!`](crate::trace).
+///
+pub fn event(file: &str, line: u32, event: &str, fields: &[(&str, &dyn Debug)]) {
+    with_tracer(|output, prefix| write_event(output, &prefix, &source(file, line), event, fields));
+}
+
+/// Writes a snapshot of a grid below a label, called by [`trace_grid!`](crate::trace_grid).
+///
+pub fn grid(file: &str, line: u32, label: &str, grid: &dyn Display) {
+    with_tracer(|output, prefix| write_grid(output, &prefix, &source(file, line), label, grid));
+}
+
+/// Traces an event with optional `key = value` fields, which are formatted with [`Debug`].
+/// Expands to nothing without the `trace` feature, so the arguments are not even evaluated.
+///
+#[cfg(feature = "trace")]
+#[macro_export]
+macro_rules! trace {
+    ($event:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::util::trace::event(file!(), line!(), $event, &[$((stringify!($key), &$value as &dyn ::std::fmt::Debug)),*])
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+#[macro_export]
+macro_rules! trace {
+    ($($arguments:tt)*) => {};
+}
+
+/// Traces a snapshot of a grid, or anything else implementing [`Display`], below a label.
+/// Expands to nothing without the `trace` feature.
+///
+#[cfg(feature = "trace")]
+#[macro_export]
+macro_rules! trace_grid {
+    ($label:expr, $grid:expr $(,)?) => {
+        $crate::util::trace::grid(file!(), line!(), $label, $grid)
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+#[macro_export]
+macro_rules! trace_grid {
+    ($($arguments:tt)*) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::grid2d::Grid2d;
+
+    #[test]
+    fn formats_events() {
+        let mut output = Vec::new();
+        let fields: [(&str, &dyn Debug); 2] = [("length", &7), ("name", &"broadcaster")];
+        write_event(&mut output, "[day 20 part 1 +1.0ms]", &source("src/bin/20.rs", 12), "cycle", &fields).unwrap();
+        write_grid(&mut output, "[day 20 part 1 +1.5ms]", "20.rs:13", "state", &Grid2d::of_lines("#.\n.#")).unwrap();
+        let expected = "[day 20 part 1 +1.0ms] 20.rs:12 cycle length=7 name=\"broadcaster\"\n\
+            [day 20 part 1 +1.5ms] 20.rs:13 state\n  #.\n  .#\n";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
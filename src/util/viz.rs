@@ -0,0 +1,160 @@
+//! Terminal visualization of grids, enabled with `cargo solve NN --viz`.
+//!
+//! Solutions build a [`Frame`] of a grid with highlighted positions and pass it to [`show`] or [`animate`].
+//! Both do nothing unless the runner enabled the visualization, so solutions can call them unconditionally
+//! and [`enabled`] only needs to be checked to skip work that is done for the visualization alone.
+
+use crate::util::grid2d::Grid2d;
+use crate::util::position::Position;
+use std::collections::HashSet;
+use std::fmt::{Display, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Returns whether the visualization was requested with `--viz`.
+/// The runner only enables it for the first run of a part, never for benchmark samples.
+///
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The ANSI colors for highlights.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+
+    /// Wraps `text` in the escape codes for this color in bold.
+    ///
+    pub fn paint(self, text: impl Display) -> String {
+        format!("\x1b[1;{}m{text}\x1b[0m", self.code())
+    }
+}
+
+/// A grid with colored positions, rendered by its [`Display`] implementation.
+/// A position in several highlights gets the color of the one added first.
+///
+/// ```
+/// # use advent_of_code::util::grid2d::Grid2d;
+/// # use advent_of_code::util::position::Position;
+/// # use advent_of_code::util::viz::{Color, Frame};
+/// let grid = Grid2d::of_lines("ab\ncd");
+/// let frame = Frame::new(&grid).highlight([Position::at(1, 0)], Color::Red);
+/// assert_eq!(frame.to_string(), "a\x1b[1;31mb\x1b[0m\ncd\n");
+/// ```
+///
+pub struct Frame<'a, T> {
+    grid: &'a Grid2d<T>,
+    title: Option<String>,
+    highlights: Vec<(HashSet<Position>, Color)>,
+}
+
+impl<'a, T> Frame<'a, T> {
+    pub fn new(grid: &'a Grid2d<T>) -> Self {
+        Frame { grid, title: None, highlights: Vec::new() }
+    }
+
+    /// Adds a line printed above the grid.
+    ///
+    pub fn title(mut self, title: impl Display) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn highlight(mut self, positions: impl IntoIterator<Item = Position>, color: Color) -> Self {
+        self.highlights.push((positions.into_iter().collect(), color));
+        self
+    }
+
+    fn color_of(&self, position: &Position) -> Option<Color> {
+        self.highlights.iter()
+            .find(|(positions, _)| positions.contains(position))
+            .map(|(_, color)| *color)
+    }
+}
+
+impl<T: Copy + PartialEq + Display> Display for Frame<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(title) = &self.title {
+            writeln!(f, "{title}")?;
+        }
+        let (width, _) = self.grid.size();
+        let mut line = String::new();
+        for position in self.grid.positions() {
+            let value = self.grid.get(&position);
+            match self.color_of(&position) {
+                Some(color) => line.push_str(&color.paint(value)),
+                None => write!(line, "{value}")?,
+            }
+            if position.x as usize == width - 1 {
+                writeln!(f, "{line}")?;
+                line.clear();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Prints `frame` if the visualization is enabled.
+///
+pub fn show(frame: impl Display) {
+    if enabled() {
+        println!("{frame}");
+    }
+}
+
+/// Plays `frames` in place, waiting `delay` after each frame, if the visualization is enabled.
+/// The frames are only rendered while they are played, so they can be created lazily.
+///
+pub fn animate<F: Display>(frames: impl IntoIterator<Item = F>, delay: Duration) {
+    if !enabled() {
+        return;
+    }
+    for frame in frames {
+        // clear the screen and move the cursor to the top left corner
+        print!("\x1b[2J\x1b[H{frame}");
+        thread::sleep(delay);
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_highlight_wins() {
+        let grid = Grid2d::of_lines("..\n..");
+        let frame = Frame::new(&grid)
+            .title("frame 1")
+            .highlight([Position::at(0, 0)], Color::Green)
+            .highlight([Position::at(0, 0), Position::at(1, 1)], Color::Blue);
+        let expected = format!("frame 1\n{}.\n.{}\n", Color::Green.paint('.'), Color::Blue.paint('.'));
+        assert_eq!(frame.to_string(), expected);
+    }
+}
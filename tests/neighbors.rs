@@ -0,0 +1,107 @@
+//! Compares the allocating `Position::neighbors` API with the iterator based `neighbors4`/`neighbors8`.
+//! Run with `cargo test --release --test neighbors -- --nocapture` to see the timings.
+
+use advent_of_code::util::grid2d::Grid2d;
+use advent_of_code::util::position::Position;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+struct CountingAllocator;
+
+thread_local! {
+    // counted per thread, so tests running in parallel do not influence each other.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|it| it.set(it.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
+
+const SIZE: usize = 100;
+
+fn grid() -> Grid2d<char> {
+    Grid2d::of_lines(&vec![".".repeat(SIZE); SIZE].join("\n"))
+}
+
+/// Runs `f` and returns the number of allocations it made together with the elapsed time.
+fn measure(f: impl FnOnce() -> i64) -> (usize, Duration) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let timer = Instant::now();
+    black_box(f());
+    let elapsed = timer.elapsed();
+    (ALLOCATIONS.with(Cell::get) - before, elapsed)
+}
+
+fn positions() -> impl Iterator<Item = Position> {
+    (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| Position::from((x, y))))
+}
+
+#[test]
+fn bench_neighbors4() {
+    let grid = grid();
+
+    let (vec_allocations, vec_time) = measure(|| {
+        positions()
+            .flat_map(|it| it.neighbors(Position::moves(false, false)))
+            .filter(|it| it.is_within(&grid))
+            .map(|it| it.x + it.y)
+            .sum()
+    });
+    let (iter_allocations, iter_time) = measure(|| {
+        positions()
+            .flat_map(|it| it.neighbors4_within(&grid))
+            .map(|it| it.x + it.y)
+            .sum()
+    });
+
+    println!("neighbors(moves): {vec_allocations} allocations, {vec_time:?}");
+    println!("neighbors4_within: {iter_allocations} allocations, {iter_time:?}");
+    assert_eq!(vec_allocations, 2 * SIZE * SIZE);
+    assert_eq!(iter_allocations, 0);
+}
+
+#[test]
+fn bench_neighbors8() {
+    let (vec_allocations, vec_time) = measure(|| {
+        positions()
+            .flat_map(|it| it.neighbors(Position::moves(true, false)))
+            .map(|it| it.x + it.y)
+            .sum()
+    });
+    let (iter_allocations, iter_time) = measure(|| {
+        positions()
+            .flat_map(|it| it.neighbors8())
+            .map(|it| it.x + it.y)
+            .sum()
+    });
+
+    println!("neighbors(moves): {vec_allocations} allocations, {vec_time:?}");
+    println!("neighbors8: {iter_allocations} allocations, {iter_time:?}");
+    assert!(vec_allocations >= 2 * SIZE * SIZE);
+    assert_eq!(iter_allocations, 0);
+}
+
+#[test]
+fn neighbors_agree() {
+    let grid = grid();
+    let corner = Position::at(0, 0);
+    assert_eq!(corner.neighbors4().collect::<Vec<_>>(), corner.neighbors(Position::moves(false, false)));
+    assert_eq!(corner.neighbors8().collect::<Vec<_>>(), corner.neighbors(Position::moves(true, false)));
+    assert_eq!(
+        corner.neighbors4_within(&grid).collect::<Vec<_>>(),
+        corner.neighbors_within(Position::moves(false, false), SIZE, SIZE)
+    );
+    assert_eq!(corner.neighbors8_within(&grid).count(), 3);
+}